@@ -0,0 +1,176 @@
+// Ошибки программы Verta.
+// Клиент получает их как ProgramError::Custom(код), где код — номер варианта.
+// Номера вариантов — часть интерфейса программы: новые ошибки добавляются только в конец.
+
+use std::fmt;
+
+use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertaError {
+    /// Подписант не имеет права выполнять эту инструкцию
+    Unauthorized = 0,
+    /// Аккаунт пользователя не зарегистрирован
+    AccountNotRegistered = 1,
+    /// Переполнение при изменении кармы или уровня
+    KarmaOverflow = 2,
+    /// Переданный адрес не совпадает с ожидаемым PDA
+    InvalidPda = 3,
+    /// Кулдаун ещё не истёк
+    CooldownActive = 4,
+    /// Превышен лимит кармы за скользящее окно
+    VelocityLimitExceeded = 5,
+    /// Пользователь пытается подтвердить собственный вклад
+    SelfVerification = 6,
+    /// Вклад уже подтверждён
+    ContributionAlreadyVerified = 7,
+    /// Глобальная конфигурация ещё не создана
+    ConfigNotInitialized = 8,
+    /// Начисление больше, чем разрешено конфигурацией
+    GrantTooLarge = 9,
+    /// Некорректные параметры конфигурации
+    InvalidConfig = 10,
+    /// Перевод кармы самому себе
+    SelfTransfer = 11,
+    /// Недостаточно кармы для операции
+    InsufficientKarma = 12,
+    /// Аккаунт другого типа (дискриминатор не совпадает)
+    InvalidAccountDiscriminator = 13,
+    /// Верификатор исчерпал лимит начислений на текущую эпоху
+    VerifierEpochCapExceeded = 14,
+    /// Неизвестная категория кармы
+    InvalidCategory = 15,
+    /// Срок действия одобрений начисления истёк
+    GrantExpired = 16,
+    /// Одобрение не от ключа из конфигурации или его подпись не проверена
+    InvalidApproval = 17,
+    /// Одобрений меньше порога из конфигурации
+    InsufficientApprovals = 18,
+    /// Аккаунт в устаревшем формате: нужна инструкция MigrateAccount
+    AccountMigrationRequired = 19,
+    /// Пользователь пытается поручиться за себя
+    SelfEndorsement = 20,
+    /// Вес поручительства вне допустимого диапазона
+    InvalidEndorsementWeight = 21,
+    /// Поручительство за этого пользователя уже есть (больше не возвращается: повторы учитываются в записи)
+    AlreadyEndorsed = 22,
+    /// В казне недостаточно лампортов для вывода
+    InsufficientTreasuryFunds = 23,
+    /// Программа на аварийной паузе
+    ProgramPaused = 24,
+    /// Аккаунт пользователя заморожен модератором
+    AccountFrozen = 25,
+    /// Код причины начисления отсутствует в таблице причин
+    UnknownReasonCode = 26,
+    /// Вес верификатора вне допустимого диапазона
+    InvalidVerifierWeight = 27,
+    /// Вклад уже оспорен
+    AlreadyChallenged = 28,
+    /// Спор по вкладу уже разрешён
+    ChallengeResolved = 29,
+    /// По вкладам пользователя есть неразрешённые споры
+    ChallengeOpen = 30,
+    /// За уровни аккаунта уже выданы награды или бейджи
+    RewardsClaimed = 31,
+    /// Исчерпан лимит веса поручительств пользователя за эпоху
+    EndorsementBudgetExceeded = 32,
+}
+
+impl VertaError {
+    // Все варианты в порядке кодов (для декодирования кода обратно в ошибку)
+    const ALL: [VertaError; 33] = [
+        VertaError::Unauthorized,
+        VertaError::AccountNotRegistered,
+        VertaError::KarmaOverflow,
+        VertaError::InvalidPda,
+        VertaError::CooldownActive,
+        VertaError::VelocityLimitExceeded,
+        VertaError::SelfVerification,
+        VertaError::ContributionAlreadyVerified,
+        VertaError::ConfigNotInitialized,
+        VertaError::GrantTooLarge,
+        VertaError::InvalidConfig,
+        VertaError::SelfTransfer,
+        VertaError::InsufficientKarma,
+        VertaError::InvalidAccountDiscriminator,
+        VertaError::VerifierEpochCapExceeded,
+        VertaError::InvalidCategory,
+        VertaError::GrantExpired,
+        VertaError::InvalidApproval,
+        VertaError::InsufficientApprovals,
+        VertaError::AccountMigrationRequired,
+        VertaError::SelfEndorsement,
+        VertaError::InvalidEndorsementWeight,
+        VertaError::AlreadyEndorsed,
+        VertaError::InsufficientTreasuryFunds,
+        VertaError::ProgramPaused,
+        VertaError::AccountFrozen,
+        VertaError::UnknownReasonCode,
+        VertaError::InvalidVerifierWeight,
+        VertaError::AlreadyChallenged,
+        VertaError::ChallengeResolved,
+        VertaError::ChallengeOpen,
+        VertaError::RewardsClaimed,
+        VertaError::EndorsementBudgetExceeded,
+    ];
+
+    /// Восстанавливает ошибку по коду из ProgramError::Custom.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.get(code as usize).copied()
+    }
+}
+
+impl fmt::Display for VertaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            VertaError::Unauthorized => "signer is not authorized to perform this action",
+            VertaError::AccountNotRegistered => "user account is not registered",
+            VertaError::KarmaOverflow => "karma arithmetic overflow",
+            VertaError::InvalidPda => "account address does not match the expected PDA",
+            VertaError::CooldownActive => "cooldown is still active",
+            VertaError::VelocityLimitExceeded => "karma velocity limit exceeded",
+            VertaError::SelfVerification => "users cannot verify their own contributions",
+            VertaError::ContributionAlreadyVerified => "contribution has already been verified",
+            VertaError::ConfigNotInitialized => "program config is not initialized",
+            VertaError::GrantTooLarge => "grant exceeds the configured maximum",
+            VertaError::InvalidConfig => "invalid config parameters",
+            VertaError::SelfTransfer => "cannot transfer karma to yourself",
+            VertaError::InsufficientKarma => "insufficient karma",
+            VertaError::InvalidAccountDiscriminator => "account has an unexpected type",
+            VertaError::VerifierEpochCapExceeded => "verifier epoch grant cap exceeded",
+            VertaError::InvalidCategory => "unknown karma category",
+            VertaError::GrantExpired => "grant approvals have expired",
+            VertaError::InvalidApproval => "invalid grant approval",
+            VertaError::InsufficientApprovals => "not enough grant approvals",
+            VertaError::AccountMigrationRequired => "account uses an outdated layout and must be migrated",
+            VertaError::SelfEndorsement => "users cannot endorse themselves",
+            VertaError::InvalidEndorsementWeight => "endorsement weight is out of range",
+            VertaError::AlreadyEndorsed => "user has already been endorsed by this endorser",
+            VertaError::InsufficientTreasuryFunds => "not enough lamports in the treasury",
+            VertaError::ProgramPaused => "program is paused",
+            VertaError::AccountFrozen => "user account is frozen",
+            VertaError::UnknownReasonCode => "karma grant reason code is not in the reason table",
+            VertaError::InvalidVerifierWeight => "verifier weight is out of range",
+            VertaError::AlreadyChallenged => "contribution has already been challenged",
+            VertaError::ChallengeResolved => "challenge has already been resolved",
+            VertaError::ChallengeOpen => "user has unresolved contribution challenges",
+            VertaError::RewardsClaimed => "level rewards or badges have already been claimed for this account",
+            VertaError::EndorsementBudgetExceeded => "endorsement budget for this epoch is exhausted",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for VertaError {}
+
+impl From<VertaError> for ProgramError {
+    fn from(error: VertaError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+impl<T> DecodeError<T> for VertaError {
+    fn type_of() -> &'static str {
+        "VertaError"
+    }
+}
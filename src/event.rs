@@ -0,0 +1,121 @@
+// События программы Verta для индексаторов.
+// Каждое событие пишется в лог транзакции одним вызовом sol_log_data:
+// 8 байт дискриминатора события, затем Borsh-данные. Дискриминаторы — часть
+// интерфейса программы и не меняются; новые поля добавляются только в новые события.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// Длина дискриминатора события.
+pub const EVENT_DISCRIMINATOR_LEN: usize = 8;
+
+/// Событие, которое программа пишет в лог.
+pub trait Event: BorshSerialize + BorshDeserialize {
+    const DISCRIMINATOR: [u8; EVENT_DISCRIMINATOR_LEN];
+
+    /// Пишет событие в лог транзакции.
+    fn emit(&self) {
+        let mut data = Self::DISCRIMINATOR.to_vec();
+        // Запись в Vec не завершается ошибкой
+        let _ = self.serialize(&mut data);
+        sol_log_data(&[&data]);
+    }
+}
+
+/// Пользователь зарегистрирован.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserRegistered {
+    pub user: Pubkey,
+    pub registered_at: i64,
+}
+
+impl Event for UserRegistered {
+    const DISCRIMINATOR: [u8; EVENT_DISCRIMINATOR_LEN] = *b"evt:user";
+}
+
+/// Пользователю начислена карма.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KarmaAdded {
+    pub user: Pubkey,
+    pub amount: u64,         // Начислено всего (включая ушедшее в overflow_karma)
+    pub category: u8,        // KARMA_CATEGORY_*
+    pub karma: u64,          // Карма после начисления
+    pub overflow_karma: u64, // overflow_karma после начисления
+    pub reason: u16,         // Код причины из таблицы причин (REASON_NONE — без причины)
+}
+
+impl Event for KarmaAdded {
+    const DISCRIMINATOR: [u8; EVENT_DISCRIMINATOR_LEN] = *b"evt:karm";
+}
+
+/// Уровень пользователя повышен.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelUp {
+    pub user: Pubkey,
+    pub level: u8, // Новый уровень
+    pub karma: u64,
+    pub timestamp: i64,
+}
+
+impl Event for LevelUp {
+    const DISCRIMINATOR: [u8; EVENT_DISCRIMINATOR_LEN] = *b"evt:lvup";
+}
+
+/// PDA пользователя пополнен лампортами (TopUpAccount).
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountToppedUp {
+    pub account: Pubkey, // PDA пользователя
+    pub payer: Pubkey,   // Кто пополнил
+    pub lamports: u64,
+}
+
+impl Event for AccountToppedUp {
+    const DISCRIMINATOR: [u8; EVENT_DISCRIMINATOR_LEN] = *b"evt:topu";
+}
+
+/// Сколько вычислительных единиц потратил обработчик инструкции
+/// (только в сборке с фичей cu-telemetry).
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeUnits {
+    pub instruction: u8, // Тип инструкции (первый байт данных инструкции)
+    pub consumed: u64,
+}
+
+impl Event for ComputeUnits {
+    const DISCRIMINATOR: [u8; EVENT_DISCRIMINATOR_LEN] = *b"evt:cuse";
+}
+
+/// Любое событие программы.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertaEvent {
+    UserRegistered(UserRegistered),
+    KarmaAdded(KarmaAdded),
+    LevelUp(LevelUp),
+    AccountToppedUp(AccountToppedUp),
+    ComputeUnits(ComputeUnits),
+}
+
+impl VertaEvent {
+    /// Декодирует данные одного вызова sol_log_data.
+    /// None — данные не являются известным событием Verta.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < EVENT_DISCRIMINATOR_LEN {
+            return None;
+        }
+        let (discriminator, mut payload) = data.split_at(EVENT_DISCRIMINATOR_LEN);
+        match discriminator {
+            d if d == UserRegistered::DISCRIMINATOR => {
+                UserRegistered::deserialize(&mut payload).ok().map(VertaEvent::UserRegistered)
+            }
+            d if d == KarmaAdded::DISCRIMINATOR => KarmaAdded::deserialize(&mut payload).ok().map(VertaEvent::KarmaAdded),
+            d if d == LevelUp::DISCRIMINATOR => LevelUp::deserialize(&mut payload).ok().map(VertaEvent::LevelUp),
+            d if d == AccountToppedUp::DISCRIMINATOR => {
+                AccountToppedUp::deserialize(&mut payload).ok().map(VertaEvent::AccountToppedUp)
+            }
+            d if d == ComputeUnits::DISCRIMINATOR => {
+                ComputeUnits::deserialize(&mut payload).ok().map(VertaEvent::ComputeUnits)
+            }
+            _ => None,
+        }
+    }
+}
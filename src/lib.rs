@@ -5,240 +5,4807 @@ use solana_program::{
     program_error::ProgramError,
     pubkey::Pubkey,
     msg,
-    // program_pack::{Pack, Sealed}, // Эти импорты не используются в текущем коде
-    borsh::{BorshDeserialize, BorshSerialize},
-    sysvar::{rent::Rent, Sysvar},
-    program::{invoke_signed},
+    program_pack::Pack,
+    sysvar::{self, clock::Clock, instructions::load_instruction_at_checked, rent::Rent, Sysvar},
+    ed25519_program,
+    program::{invoke, invoke_signed, set_return_data},
     system_instruction,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
 };
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_associated_token_account::{get_associated_token_address, get_associated_token_address_with_program_id};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::ExtensionType;
+use mpl_token_metadata::{
+    accounts::{MasterEdition, Metadata},
+    instructions::{
+        CreateMasterEditionV3Cpi, CreateMasterEditionV3CpiAccounts, CreateMasterEditionV3InstructionArgs,
+        CreateMetadataAccountV3Cpi, CreateMetadataAccountV3CpiAccounts, CreateMetadataAccountV3InstructionArgs,
+        UpdateMetadataAccountV2Cpi, UpdateMetadataAccountV2CpiAccounts, UpdateMetadataAccountV2InstructionArgs,
+    },
+    types::{Collection, DataV2},
+};
+// Кривая уровней общая с клиентами (крейт verta-math)
+use verta_math::{decay::decay, level::LevelCurve};
+use verta_math::velocity::{advance_window, day_index, window_total, WINDOW_DAYS};
+#[cfg(feature = "cu-telemetry")]
+use solana_program::compute_units::sol_remaining_compute_units;
+
+pub mod error;
+pub use error::VertaError;
+
+pub mod event;
+use event::{AccountToppedUp, Event, KarmaAdded, LevelUp, UserRegistered};
+
+// Клиентский SDK: работает поверх RPC и не входит в сборку on-chain программы
+#[cfg(not(target_os = "solana"))]
+pub mod sdk;
+
+// Длина дискриминатора, с которого начинаются данные каждого аккаунта программы
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+// Аккаунт программы Verta. Данные аккаунта: дискриминатор типа, затем Borsh-структура.
+// Дискриминатор не даёт передать аккаунт одного типа туда, где ожидается другой:
+// без него такой аккаунт мог бы «успешно» десериализоваться в мусор.
+pub trait VertaAccount: BorshSerialize + BorshDeserialize {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+
+    // Начинаются ли данные с дискриминатора этого типа
+    fn has_discriminator(data: &[u8]) -> bool {
+        data.get(..DISCRIMINATOR_LEN) == Some(&Self::DISCRIMINATOR[..])
+    }
 
-// Импортируем необходимые трейты напрямую из borsh, если solana_program не переэкспортирует их публично в этой версии
-// (или оставляем импорт из solana_program::borsh, если он работает)
-// В предыдущем шаге сработал вариант импорта через solana_program::borsh.
-// Поэтому оставляем его. Если возникнут проблемы, попробуем импорт напрямую из borsh.
-// use borsh::{BorshDeserialize, BorshSerialize}; // Эту строку удалили в предыдущем шаге
+    // Декодирует данные аккаунта, проверяя дискриминатор
+    fn try_from_account_data(data: &[u8]) -> std::io::Result<Self> {
+        if !Self::has_discriminator(data) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "account discriminator mismatch",
+            ));
+        }
+        Self::deserialize(&mut &data[DISCRIMINATOR_LEN..])
+    }
+
+    // Записывает дискриминатор и данные в аккаунт
+    fn write_account_data(&self, data: &mut [u8]) -> std::io::Result<()> {
+        if data.len() < DISCRIMINATOR_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WriteZero,
+                "account data is too small",
+            ));
+        }
+        let (discriminator, rest) = data.split_at_mut(DISCRIMINATOR_LEN);
+        discriminator.copy_from_slice(&Self::DISCRIMINATOR);
+        self.serialize(&mut &mut rest[..])
+    }
+}
 
 // Определение структуры аккаунта пользователя
-#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Eq, Default)]
 pub struct UserAccount {
+    pub version: u8, // Версия схемы (USER_ACCOUNT_SCHEMA_VERSION); старые аккаунты обновляет MigrateAccount
     pub karma: u64, // Количество кармы пользователя
     pub level: u8,  // Уровень пользователя
+    pub last_level_up: i64, // Время последнего повышения уровня (unix timestamp)
+    pub identity_hash: [u8; 32], // Солёный хеш внешней идентичности (GitHub/Discord); нули — не привязана
+    pub karma_window: [u64; WINDOW_DAYS], // Карма, начисленная за последние дни ([0] — текущий день)
+    pub window_day: i64, // Номер дня, которому соответствует karma_window[0]
+    pub overflow_karma: u64, // Карма, заработанная сверх максимального уровня (для престижа и сезонных очков)
+    pub owner: Pubkey, // Кошелёк пользователя, из которого выведен адрес PDA
+    pub bump: u8, // Бамп PDA: адрес проверяется без перебора find_program_address
+    pub registered_at: i64, // Время регистрации (unix timestamp)
+    pub last_updated: i64, // Время последнего изменения аккаунта (unix timestamp)
+    pub category_karma: [u64; KARMA_CATEGORIES], // Заработанная карма по категориям (KARMA_CATEGORY_*); списания её не уменьшают
+    pub grant_nonce: u64, // Номер следующего многоподписного начисления (защита от повторного использования одобрений)
+    pub frozen: bool, // Заморожен модератором: не получает, не переводит и не тратит карму
+    pub last_decay_at: i64, // С какого момента DecayKarma считает затухание (unix timestamp)
+    pub rewarded_level: u8, // Наибольший уровень, за который уже выдана награда (повторно уровни не награждаются)
+    pub badges_awarded: [u8; 32], // Битовая маска уровней, за которые уже выдан бейдж (бит `level`)
+    pub open_challenges: u32, // Неразрешённые споры по вкладам пользователя: пока они есть, аккаунт нельзя закрыть
+    pub rent_payer: Pubkey, // Кто внёс ренту при регистрации (владелец или спонсор): получает её при закрытии аккаунта
+    pub endorsement_epoch: u64, // Эпоха, за которую считается endorsed_in_epoch
+    pub endorsed_in_epoch: u64, // Суммарный вес поручительств пользователя за endorsement_epoch
+    pub strikes: u8, // Предупреждения модераторов (IssueStrike); сгорают после испытательного срока без новых
+    pub last_strike_at: i64, // Время последнего предупреждения (unix timestamp)
     // Можно добавить другие поля позже, например:
     // pub verified_contributions: u32, // Количество подтвержденных вкладов
-    // pub registration_time: i64,     // Время регистрации
     // pub latest_contribution_type: u8, // Тип последнего вклада
 }
 
+impl VertaAccount for UserAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:user";
+}
+
+// Категории кармы: индекс в UserAccount::category_karma
+pub const KARMA_CATEGORY_CODE: u8 = 0;
+pub const KARMA_CATEGORY_REVIEW: u8 = 1;
+pub const KARMA_CATEGORY_DOCS: u8 = 2;
+pub const KARMA_CATEGORY_COMMUNITY: u8 = 3;
+pub const KARMA_CATEGORIES: usize = 4;
+
 // Определение размера структуры в байтах
-// u64 = 8 байт, u8 = 1 байт. Общий размер: 8 + 1 = 9 байт.
+// u64 = 8 байт, u8 = 1 байт, i64 = 8 байт, [u8; 32] = 32 байта, [u64; 7] = 56 байт, Pubkey = 32 байта, [u64; 4] = 32 байта, bool = 1 байт.
+// Общий размер: 8 (дискриминатор) + 1 + 8 + 1 + 8 + 32 + 56 + 8 + 8 + 32 + 1 + 8 + 8 + 32 + 8 + 1 + 8 + 1 + 32 + 4 + 32 + 8 + 8 + 1 + 8 = 322 байта.
 impl UserAccount {
-    pub const LEN: usize = 8 + 1; // Плюс потенциальные байты для других полей
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8 + 32 + 1 + 8 + 8 + 8 * KARMA_CATEGORIES + 8 + 1 + 8 + 1 + 32 + 4 + 32 + 8 + 8 + 1 + 8; // Плюс потенциальные байты для других полей
+
+    // Смещения полей в данных аккаунта (для частичной загрузки через dataSlice)
+    pub const VERSION_OFFSET: usize = DISCRIMINATOR_LEN;
+    pub const KARMA_OFFSET: usize = DISCRIMINATOR_LEN + 1;
+    pub const LEVEL_OFFSET: usize = DISCRIMINATOR_LEN + 1 + 8;
+    pub const OWNER_OFFSET: usize = DISCRIMINATOR_LEN + 1 + 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8;
+
+    // Действующие предупреждения на момент `now`: все они сгорают, если с последнего
+    // прошло не меньше `probation` секунд (0 — не сгорают)
+    pub fn active_strikes(&self, now: i64, probation: i64) -> u8 {
+        if probation > 0 && now.saturating_sub(self.last_strike_at) >= probation {
+            0
+        } else {
+            self.strikes
+        }
+    }
+
+    // Выдан ли пользователю бейдж за уровень `level`
+    pub fn has_badge(&self, level: u8) -> bool {
+        self.badges_awarded[level as usize / 8] & (1 << (level % 8)) != 0
+    }
+
+    // Отмечает бейдж за уровень `level` выданным
+    pub fn set_badge(&mut self, level: u8) {
+        self.badges_awarded[level as usize / 8] |= 1 << (level % 8);
+    }
+
+    // Билдер для тестов, фикстур и клиентского кода
+    pub fn builder() -> UserAccountBuilder {
+        UserAccountBuilder {
+            account: UserAccount {
+                version: USER_ACCOUNT_SCHEMA_VERSION,
+                ..UserAccount::default()
+            },
+        }
+    }
 }
 
-// Определение возможных инструкций для нашей программы
-#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
-pub enum VertaInstruction {
-    /// Инструкция 0: Регистрация пользователя.
-    /// Создает или инициализирует аккаунт пользователя (PDA).
-    RegisterUser,
+// Аккаунт пользователя схемы версии 5 — последний формат без поля version.
+// Такие аккаунты распознаются по длине данных и обновляются инструкцией MigrateAccount.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct UserAccountV5 {
+    pub karma: u64,
+    pub level: u8,
+    pub last_level_up: i64,
+    pub identity_hash: [u8; 32],
+    pub karma_window: [u64; WINDOW_DAYS],
+    pub window_day: i64,
+    pub overflow_karma: u64,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub registered_at: i64,
+    pub last_updated: i64,
+    pub category_karma: [u64; KARMA_CATEGORIES],
+    pub grant_nonce: u64,
+}
 
-    /// Инструкция 1: Добавить карму.
-    /// Добавляет указанное количество кармы пользователю.
-    /// Data: [1 (instruction_type), amount: u64]
-    AddKarma { amount: u64 }, // Пример: добавить amount кармы
+impl VertaAccount for UserAccountV5 {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = UserAccount::DISCRIMINATOR;
+}
 
-    /// Инструкция 2: Обновить уровень.
-    /// Пересчитывает уровень пользователя на основе текущей кармы.
-    /// Data: [2 (instruction_type)]
-    UpdateLevel,
+impl UserAccountV5 {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8 + 32 + 1 + 8 + 8 + 8 * KARMA_CATEGORIES + 8;
+}
 
-    // Можно добавить другие инструкции, например:
-    // /// Инструкция 3: Подтвердить вклад другого пользователя
-    // VerifyContribution { user_to_verify: Pubkey, contribution_id: u64 },
+// Все схемы аккаунта пользователя без поля version в порядке появления. Каждая дописывала поля
+// в конец предыдущей, поэтому данные любой из них (после дискриминатора, если он есть) — начало
+// формата UserAccountV5. Длины всех схем различны, схема определяется по длине данных.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnversionedUserLayout {
+    Basic,         // karma, level
+    LevelCooldown, // + last_level_up
+    Identity,      // + identity_hash
+    KarmaWindow,   // + karma_window, window_day
+    Overflow,      // + overflow_karma
+    Owner,         // + owner, bump, registered_at, last_updated
+    Discriminator, // дискриминатор перед данными
+    CategoryKarma, // + category_karma
+    GrantNonce,    // + grant_nonce (UserAccountV5)
 }
 
-// Главная точка входа в программу
-entrypoint!(process_instruction);
+impl UnversionedUserLayout {
+    pub const ALL: [UnversionedUserLayout; 9] = [
+        UnversionedUserLayout::Basic,
+        UnversionedUserLayout::LevelCooldown,
+        UnversionedUserLayout::Identity,
+        UnversionedUserLayout::KarmaWindow,
+        UnversionedUserLayout::Overflow,
+        UnversionedUserLayout::Owner,
+        UnversionedUserLayout::Discriminator,
+        UnversionedUserLayout::CategoryKarma,
+        UnversionedUserLayout::GrantNonce,
+    ];
 
-// Основная функция обработки инструкций
-fn process_instruction(
-    program_id: &Pubkey,        // ID вашей программы
-    accounts: &[AccountInfo],   // Список аккаунтов, участвующих в транзакции
-    instruction_data: &[u8],    // Данные инструкции (определяют, что делать)
-) -> ProgramResult {
-    msg!("Verta Program Entrypoint"); // Отладочное сообщение в начале
+    // Длина данных аккаунта этой схемы
+    pub const fn data_len(self) -> usize {
+        match self {
+            UnversionedUserLayout::Basic => 8 + 1,
+            UnversionedUserLayout::LevelCooldown => 8 + 1 + 8,
+            UnversionedUserLayout::Identity => 8 + 1 + 8 + 32,
+            UnversionedUserLayout::KarmaWindow => 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8,
+            UnversionedUserLayout::Overflow => 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8,
+            UnversionedUserLayout::Owner => 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8 + 32 + 1 + 8 + 8,
+            UnversionedUserLayout::Discriminator => DISCRIMINATOR_LEN + 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8 + 32 + 1 + 8 + 8,
+            UnversionedUserLayout::CategoryKarma => {
+                DISCRIMINATOR_LEN + 8 + 1 + 8 + 32 + 8 * WINDOW_DAYS + 8 + 8 + 32 + 1 + 8 + 8 + 8 * KARMA_CATEGORIES
+            }
+            UnversionedUserLayout::GrantNonce => UserAccountV5::LEN,
+        }
+    }
 
-    // Десериализация данных инструкции
-    let instruction = VertaInstruction::try_from_slice(instruction_data)
-        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    // Схема по длине данных (None — длина не встречалась ни в одной схеме без поля version)
+    pub fn from_len(len: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|layout| layout.data_len() == len)
+    }
 
-    msg!("Received instruction: {:?}", instruction); // Отладочное сообщение с типом инструкции
+    // Начинаются ли данные с дискриминатора (он появился в этой последовательности с Discriminator)
+    pub fn has_discriminator(self) -> bool {
+        matches!(
+            self,
+            UnversionedUserLayout::Discriminator | UnversionedUserLayout::CategoryKarma | UnversionedUserLayout::GrantNonce
+        )
+    }
 
-    // Используем match для вызова нужной функции-обработчика
-    match instruction {
-        VertaInstruction::RegisterUser => {
-            msg!("Processing RegisterUser instruction");
-            process_register_user(program_id, accounts)
+    // Хранит ли схема кошелёк владельца и бамп PDA
+    pub fn has_owner(self) -> bool {
+        !matches!(
+            self,
+            UnversionedUserLayout::Basic
+                | UnversionedUserLayout::LevelCooldown
+                | UnversionedUserLayout::Identity
+                | UnversionedUserLayout::KarmaWindow
+                | UnversionedUserLayout::Overflow
+        )
+    }
+
+    // Декодирует данные этой схемы; отсутствующие в ней поля получают нулевые значения
+    pub fn decode(self, data: &[u8]) -> Result<UserAccountV5, ProgramError> {
+        if data.len() != self.data_len() {
+            return Err(ProgramError::InvalidAccountData);
         }
-        VertaInstruction::AddKarma { amount } => {
-            msg!("Processing AddKarma instruction");
-            process_add_karma(program_id, accounts, amount)
+        let body = if self.has_discriminator() {
+            if !UserAccountV5::has_discriminator(data) {
+                return Err(VertaError::InvalidAccountDiscriminator.into());
+            }
+            &data[DISCRIMINATOR_LEN..]
+        } else {
+            data
+        };
+        let mut padded = body.to_vec();
+        padded.resize(UserAccountV5::LEN - DISCRIMINATOR_LEN, 0);
+        UserAccountV5::try_from_slice(&padded).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+// Длины аккаунта пользователя схем с полем version. Новые поля добавляются только в конец,
+// поэтому данные старой схемы — начало текущего формата: 13 — без предупреждений модераторов,
+// 12 — ещё и без счётчиков поручительств за эпоху, 11 — ещё и без rent_payer, 10 — ещё и без open_challenges, 9 — ещё и без badges_awarded, 8 — ещё и без rewarded_level,
+// 7 — ещё и без last_decay_at, 6 — ещё и без frozen
+pub const USER_ACCOUNT_V13_LEN: usize = UserAccount::LEN - 9;
+pub const USER_ACCOUNT_V12_LEN: usize = USER_ACCOUNT_V13_LEN - 16;
+pub const USER_ACCOUNT_V11_LEN: usize = USER_ACCOUNT_V12_LEN - 32;
+pub const USER_ACCOUNT_V10_LEN: usize = USER_ACCOUNT_V11_LEN - 4;
+pub const USER_ACCOUNT_V9_LEN: usize = USER_ACCOUNT_V10_LEN - 32;
+pub const USER_ACCOUNT_V8_LEN: usize = USER_ACCOUNT_V9_LEN - 1;
+pub const USER_ACCOUNT_V7_LEN: usize = USER_ACCOUNT_V8_LEN - 8;
+pub const USER_ACCOUNT_V6_LEN: usize = USER_ACCOUNT_V7_LEN - 1;
+
+// Длина данных аккаунта пользователя схемы `version` (None — схема без поля version или неизвестная)
+pub fn user_account_len(version: u8) -> Option<usize> {
+    match version {
+        6 => Some(USER_ACCOUNT_V6_LEN),
+        7 => Some(USER_ACCOUNT_V7_LEN),
+        8 => Some(USER_ACCOUNT_V8_LEN),
+        9 => Some(USER_ACCOUNT_V9_LEN),
+        10 => Some(USER_ACCOUNT_V10_LEN),
+        11 => Some(USER_ACCOUNT_V11_LEN),
+        12 => Some(USER_ACCOUNT_V12_LEN),
+        13 => Some(USER_ACCOUNT_V13_LEN),
+        USER_ACCOUNT_SCHEMA_VERSION => Some(UserAccount::LEN),
+        _ => None,
+    }
+}
+
+impl From<UserAccountV5> for UserAccount {
+    fn from(old: UserAccountV5) -> Self {
+        UserAccount {
+            version: USER_ACCOUNT_SCHEMA_VERSION,
+            karma: old.karma,
+            level: old.level,
+            last_level_up: old.last_level_up,
+            identity_hash: old.identity_hash,
+            karma_window: old.karma_window,
+            window_day: old.window_day,
+            overflow_karma: old.overflow_karma,
+            owner: old.owner,
+            bump: old.bump,
+            registered_at: old.registered_at,
+            last_updated: old.last_updated,
+            category_karma: old.category_karma,
+            grant_nonce: old.grant_nonce,
+            frozen: false,
+            // Поля новых схем заполняет upgrade_from
+            ..UserAccount::default()
         }
-        VertaInstruction::UpdateLevel => {
-            msg!("Processing UpdateLevel instruction");
-            process_update_level(program_id, accounts)
+    }
+}
+
+impl UserAccount {
+    // Заполняет поля, которых не было в схеме `version` (данные старой схемы дополнены нулями)
+    fn upgrade_from(&mut self, version: u8) {
+        if version < 8 {
+            self.last_decay_at = self.last_updated;
         }
-        // Добавьте ветки для других инструкций
-        // VertaInstruction::VerifyContribution { user_to_verify, contribution_id } => {
-        //     msg!("Processing VerifyContribution instruction");
-        //     process_verify_contribution(program_id, accounts, user_to_verify, contribution_id)
-        // }
+        if version < 9 {
+            // Награды за уже достигнутые уровни считаются выданными
+            self.rewarded_level = self.level;
+        }
+        if version < 10 {
+            // Бейджи за уже достигнутые уровни считаются выданными (биты уровней без бейджа не проверяются)
+            for level in 1..=self.level {
+                self.set_badge(level);
+            }
+        }
+        if version < 12 {
+            // Кто платил ренту, не сохранялось: при закрытии она достаётся владельцу, как и раньше
+            self.rent_payer = self.owner;
+        }
+        self.version = USER_ACCOUNT_SCHEMA_VERSION;
     }
 }
 
-// --- Функции-обработчики инструкций ---
+// Билдер UserAccount: незаданные поля получают значения по умолчанию,
+// версия — текущая USER_ACCOUNT_SCHEMA_VERSION
+#[derive(Debug, Clone)]
+pub struct UserAccountBuilder {
+    account: UserAccount,
+}
 
-// Обработчик инструкции RegisterUser
-fn process_register_user(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-) -> ProgramResult {
-    msg!("Entering process_register_user");
+impl UserAccountBuilder {
+    pub fn version(mut self, version: u8) -> Self {
+        self.account.version = version;
+        self
+    }
 
-    let accounts_iter = &mut accounts.iter();
+    pub fn karma(mut self, karma: u64) -> Self {
+        self.account.karma = karma;
+        self
+    }
 
-    // Требуемые аккаунты для этой инструкции
-    let user = next_account_info(accounts_iter)?; // Аккаунт пользователя (подписывает создание)
-    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт для хранения данных
-    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунта
+    pub fn level(mut self, level: u8) -> Self {
+        self.account.level = level;
+        self
+    }
 
-    // Проверки аккаунтов
-    if !user.is_signer {
-        msg!("User account must be a signer for registration");
-        return Err(ProgramError::MissingRequiredSignature);
+    pub fn last_level_up(mut self, last_level_up: i64) -> Self {
+        self.account.last_level_up = last_level_up;
+        self
     }
 
-    // Проверка PDA
-    let (pda, bump) = Pubkey::find_program_address(&[b"user", user.key.as_ref()], program_id);
-    if pda != *user_pda.key {
-        msg!("Invalid PDA address provided for registration");
-        return Err(ProgramError::InvalidArgument);
+    pub fn identity_hash(mut self, identity_hash: [u8; 32]) -> Self {
+        self.account.identity_hash = identity_hash;
+        self
     }
 
-    // Проверка, что аккаунт PDA не используется другой программой
-    if user_pda.owner != program_id && !user_pda.data_is_empty() {
-         msg!("Provided PDA account is owned by another program");
-         return Err(ProgramError::IncorrectProgramId);
+    pub fn overflow_karma(mut self, overflow_karma: u64) -> Self {
+        self.account.overflow_karma = overflow_karma;
+        self
     }
 
-    // Если аккаунт PDA пустой, создаем его
-    if user_pda.data_is_empty() {
-        msg!("Creating user account (PDA)");
+    pub fn owner(mut self, owner: Pubkey) -> Self {
+        self.account.owner = owner;
+        self
+    }
 
-        let space = UserAccount::LEN; // Размер аккаунта
-        let rent_required = Rent::get()?.minimum_balance(space); // Требуемый баланс для ренты
+    pub fn bump(mut self, bump: u8) -> Self {
+        self.account.bump = bump;
+        self
+    }
 
-        // Инструкция для создания аккаунта через системную программу
-        let create_account_instruction = &system_instruction::create_account(
-            user.key,          // Отправитель (пользователь)
-            user_pda.key,      // Получатель (PDA)
-            rent_required,     // Необходимый баланс для ренты
-            space as u64,      // Размер аккаунта в байтах
-            program_id,        // Владелец аккаунта (наша программа)
-        );
+    pub fn registered_at(mut self, registered_at: i64) -> Self {
+        self.account.registered_at = registered_at;
+        self
+    }
 
-        // Вызов инструкции создания аккаунта с подписью PDA
-        invoke_signed(
-            create_account_instruction,
-            &[user.clone(), user_pda.clone(), system_program.clone()], // Аккаунты, участвующие в инструкции
-            &[&[b"user", user.key.as_ref(), &[bump]]], // Сиды и бамп для подписи PDA
-        )?;
+    pub fn last_updated(mut self, last_updated: i64) -> Self {
+        self.account.last_updated = last_updated;
+        self
+    }
 
-        // Инициализация данных в новом аккаунте
-        let account_data = UserAccount { karma: 0, level: 0 }; // Начальные значения кармы и уровня
-        BorshSerialize::serialize(&account_data, &mut &mut user_pda.data.borrow_mut()[..])?;
+    pub fn category_karma(mut self, category_karma: [u64; KARMA_CATEGORIES]) -> Self {
+        self.account.category_karma = category_karma;
+        self
+    }
 
-        msg!("User account created and initialized successfully");
+    pub fn grant_nonce(mut self, grant_nonce: u64) -> Self {
+        self.account.grant_nonce = grant_nonce;
+        self
+    }
 
-    } else {
-        msg!("User account already exists. Skipping creation.");
-        // Можно добавить логику для повторной инициализации, если нужно
+    pub fn frozen(mut self, frozen: bool) -> Self {
+        self.account.frozen = frozen;
+        self
     }
 
-    Ok(()) // Успешное выполнение инструкции
+    pub fn last_decay_at(mut self, last_decay_at: i64) -> Self {
+        self.account.last_decay_at = last_decay_at;
+        self
+    }
+
+    pub fn rewarded_level(mut self, rewarded_level: u8) -> Self {
+        self.account.rewarded_level = rewarded_level;
+        self
+    }
+
+    pub fn badges_awarded(mut self, badges_awarded: [u8; 32]) -> Self {
+        self.account.badges_awarded = badges_awarded;
+        self
+    }
+
+    pub fn open_challenges(mut self, open_challenges: u32) -> Self {
+        self.account.open_challenges = open_challenges;
+        self
+    }
+
+    pub fn rent_payer(mut self, rent_payer: Pubkey) -> Self {
+        self.account.rent_payer = rent_payer;
+        self
+    }
+
+    pub fn endorsement_epoch(mut self, endorsement_epoch: u64) -> Self {
+        self.account.endorsement_epoch = endorsement_epoch;
+        self
+    }
+
+    pub fn endorsed_in_epoch(mut self, endorsed_in_epoch: u64) -> Self {
+        self.account.endorsed_in_epoch = endorsed_in_epoch;
+        self
+    }
+
+    pub fn strikes(mut self, strikes: u8) -> Self {
+        self.account.strikes = strikes;
+        self
+    }
+
+    pub fn last_strike_at(mut self, last_strike_at: i64) -> Self {
+        self.account.last_strike_at = last_strike_at;
+        self
+    }
+
+    pub fn build(self) -> UserAccount {
+        self.account
+    }
 }
 
-// Обработчик инструкции AddKarma
-fn process_add_karma(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    amount: u64,
-) -> ProgramResult {
-    msg!("Entering process_add_karma");
+// Запись о подтверждённом вкладе (PDA с сидами [b"contribution", contributor, contribution_id]).
+// Хранит, кто и когда подтвердил вклад и сколько кармы за него начислено,
+// чтобы у каждого начисления было on-chain обоснование.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ContributionAccount {
+    pub contributor: Pubkey,  // Автор вклада
+    pub contribution_id: u64, // Идентификатор вклада у автора
+    pub verifier: Pubkey,     // Кто подтвердил вклад
+    pub verified_at: i64,     // Время подтверждения (unix timestamp)
+    pub karma_awarded: u64,   // Сколько кармы начислено за вклад
+}
 
-    let accounts_iter = &mut accounts.iter();
+impl VertaAccount for ContributionAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:cntb";
+}
 
-    // Требуемые аккаунты: пользователь, которому добавляем карму, и его PDA
-    let user_to_update_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
-    // Возможно, потребуется аккаунт того, кто добавляет карму (верификатор)
-    // let verifier = next_account_info(accounts_iter)?;
+// Спор по подтверждённому вкладу (PDA с сидами [b"challenge", contribution_pda]).
+// Пока спор открыт, карма за вклад снята с автора и учтена в karma_locked, а залог
+// оспаривающего лежит на PDA сверх ренты. После ResolveChallenge запись остаётся,
+// поэтому один вклад оспаривается только один раз.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChallengeAccount {
+    pub contribution: Pubkey, // PDA оспоренного вклада
+    pub contributor: Pubkey,  // Автор вклада
+    pub challenger: Pubkey,   // Кто оспорил
+    pub stake_lamports: u64,  // Залог оспаривающего
+    pub karma_locked: u64,    // Карма, снятая с автора на время спора
+    pub opened_at: i64,       // Время открытия спора (unix timestamp)
+    pub resolved: bool,       // Спор разрешён
+    pub upheld: bool,         // Спор удовлетворён: карма за вклад отозвана
+}
 
-    // TODO: Добавить проверки аккаунтов (например, что user_to_update_pda принадлежит этой программе)
-    // TODO: Реализовать логику проверки, кто может добавить карму (защита от абуза)
-    // Например, проверить, что verifier подписал транзакцию и имеет достаточную репутацию.
+impl VertaAccount for ChallengeAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:chlg";
+}
 
-    // Десериализуем данные аккаунта PDA
-    let mut account_data = UserAccount::try_from_slice(&user_to_update_pda.data.borrow())?;
-    
-    // Обновляем карму
-    account_data.karma += amount;
-    msg!("Adding {} karma. New karma: {}", amount, account_data.karma);
+// Общий размер: 8 (дискриминатор) + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1 = 130 байт.
+impl ChallengeAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 32 + 32 + 32 + 8 + 8 + 8 + 1 + 1;
+}
 
-    // Сериализуем обновленные данные обратно в аккаунт
-    BorshSerialize::serialize(&account_data, &mut &mut user_to_update_pda.data.borrow_mut())?;
+// Pubkey = 32 байта, u64 = 8 байт, i64 = 8 байт. Общий размер: 8 (дискриминатор) + 32 + 8 + 32 + 8 + 8 = 96 байт.
+impl ContributionAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 32 + 8 + 32 + 8 + 8;
+}
 
-    // TODO: Возможно, здесь же вызывать process_update_level, или сделать это отдельной инструкцией
+// Параметры конфигурации, задаваемые администратором
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub level_curve_kind: u8,     // Форма кривой уровней: LEVEL_CURVE_LINEAR или LEVEL_CURVE_GEOMETRIC
+    pub level_curve_base: u64,    // Линейная: карма на уровень; геометрическая: стоимость первого уровня
+    pub level_curve_growth: u64,  // Геометрическая: множитель стоимости уровня (fixed::SCALE = 1.0)
+    pub max_karma_per_grant: u64, // Максимум кармы за одно начисление
+    pub transfer_fee_bps: u16,      // Комиссия TransferKarma в базисных пунктах (10_000 = 100%)
+    pub transfer_fee_destination: u8, // Куда уходит комиссия: TRANSFER_FEE_BURN или TRANSFER_FEE_TREASURY
+    pub karma_half_life: u64,       // Период полураспада кармы в секундах для DecayKarma (0 — без затухания)
+    pub verifier_epoch_cap: u64,    // Максимум кармы, который один верификатор начисляет за эпоху (0 — без лимита)
+    pub grant_cosigners: [Pubkey; MAX_GRANT_COSIGNERS], // Ключи, одобряющие многоподписные начисления (Pubkey::default() — пустой слот)
+    pub grant_threshold: u8,        // Сколько одобрений нужно для AddKarmaWithApprovals (0 — инструкция выключена)
+    pub min_verifier_level: u8,     // Уровень, с которого пользователь может начислять карму (0 — только администратор)
+    pub registration_fee_lamports: u64, // Плата за регистрацию, зачисляемая в казну (0 — бесплатно)
+    pub reward_mint: Pubkey,        // SPL-токен награды за уровень (Pubkey::default() — награды выключены)
+    pub reward_per_level: u64,      // Сколько базовых единиц токена получает пользователь за новый уровень
+    pub badge_levels: [u8; MAX_BADGE_LEVELS], // Уровни, за которые выдаётся непередаваемый бейдж (0 — пустой слот)
+    pub badge_collection: Pubkey,   // Mint коллекции NFT-бейджей Metaplex (Pubkey::default() — MintBadgeNft выключена)
+    pub badge_uri_base: [u8; MAX_BADGE_URI_LEN], // Начало URI метаданных NFT-бейджа (UTF-8, дополнено нулями)
+    pub challenge_stake_lamports: u64, // Залог за оспаривание вклада (0 — оспаривание выключено)
+    pub level_up_cooldown: i64,     // Минимальное время между двумя повышениями уровня в секундах
+    pub karma_velocity_limit: u64,  // Максимум кармы пользователю за скользящее окно из WINDOW_DAYS дней (0 — без лимита)
+    pub max_level: u8,              // Максимальный уровень: карма сверх его порога копится в overflow_karma
+    pub endorsement_epoch_budget: u64, // Суммарный вес поручительств одного пользователя за эпоху (0 — без лимита)
+    pub strike_freeze_threshold: u8, // Сколько действующих предупреждений замораживают аккаунт (0 — не замораживать)
+    pub strike_probation_secs: i64, // Испытательный срок: через столько секунд без новых предупреждений они сгорают (0 — никогда)
+}
 
-    msg!("AddKarma instruction processed successfully");
+// Максимальное число уровней в ConfigParams::badge_levels
+pub const MAX_BADGE_LEVELS: usize = 3;
 
-    Ok(()) // Успешное выполнение инструкции
+// Максимальная длина ConfigParams::badge_uri_base в байтах
+pub const MAX_BADGE_URI_LEN: usize = 64;
+
+// Имя и символ NFT-бейджа в метаданных Metaplex
+pub const BADGE_NFT_SYMBOL: &str = "VRTA";
+
+// Максимальное число ключей в ConfigParams::grant_cosigners
+pub const MAX_GRANT_COSIGNERS: usize = 5;
+
+// Формы кривой уровней в ConfigParams::level_curve_kind
+pub const LEVEL_CURVE_LINEAR: u8 = 0;
+pub const LEVEL_CURVE_GEOMETRIC: u8 = 1;
+
+// Назначение комиссии в ConfigParams::transfer_fee_destination
+pub const TRANSFER_FEE_BURN: u8 = 0;
+pub const TRANSFER_FEE_TREASURY: u8 = 1;
+
+// Базисных пунктов в 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+impl Default for ConfigParams {
+    fn default() -> Self {
+        Self {
+            level_curve_kind: LEVEL_CURVE_LINEAR,
+            level_curve_base: verta_math::level::KARMA_PER_LEVEL,
+            level_curve_growth: 0,
+            max_karma_per_grant: 1_000,
+            transfer_fee_bps: 0,
+            transfer_fee_destination: TRANSFER_FEE_BURN,
+            karma_half_life: 0,
+            verifier_epoch_cap: 0,
+            grant_cosigners: [Pubkey::default(); MAX_GRANT_COSIGNERS],
+            grant_threshold: 0,
+            min_verifier_level: 0,
+            registration_fee_lamports: 0,
+            reward_mint: Pubkey::default(),
+            reward_per_level: 0,
+            // Бейджи выключены: сначала администратор создаёт их минты (InitializeBadgeMint),
+            // затем задаёт уровни, например [10, 25, 50]
+            badge_levels: [0; MAX_BADGE_LEVELS],
+            badge_collection: Pubkey::default(),
+            badge_uri_base: [0; MAX_BADGE_URI_LEN],
+            challenge_stake_lamports: 0,
+            level_up_cooldown: LEVEL_UP_COOLDOWN,
+            karma_velocity_limit: verta_math::velocity::DEFAULT_VELOCITY_LIMIT,
+            max_level: verta_math::level::DEFAULT_MAX_LEVEL,
+            endorsement_epoch_budget: DEFAULT_ENDORSEMENT_EPOCH_BUDGET,
+            strike_freeze_threshold: DEFAULT_STRIKE_FREEZE_THRESHOLD,
+            strike_probation_secs: DEFAULT_STRIKE_PROBATION_SECS,
+        }
+    }
 }
 
-// Обработчик инструкции UpdateLevel
-fn process_update_level(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-) -> ProgramResult {
-    msg!("Entering process_update_level");
+impl ConfigParams {
+    // Кривая уровней из параметров (None — параметры некорректны)
+    pub fn level_curve(&self) -> Option<LevelCurve> {
+        let curve = match self.level_curve_kind {
+            LEVEL_CURVE_LINEAR => LevelCurve::Linear {
+                karma_per_level: self.level_curve_base,
+            },
+            LEVEL_CURVE_GEOMETRIC => LevelCurve::Geometric {
+                base_karma: self.level_curve_base,
+                growth: self.level_curve_growth,
+            },
+            _ => return None,
+        };
+        Some(curve).filter(LevelCurve::is_valid)
+    }
 
-    let accounts_iter = &mut accounts.iter();
+    // Проверка всех параметров перед записью в конфигурацию
+    pub fn is_valid(&self) -> bool {
+        self.level_curve().is_some()
+            && self.transfer_fee_bps as u64 <= BPS_DENOMINATOR
+            && matches!(self.transfer_fee_destination, TRANSFER_FEE_BURN | TRANSFER_FEE_TREASURY)
+            && self.grant_threshold as usize <= self.grant_cosigner_count()
+            && self.max_level > 0
+            && self.badge_levels.iter().all(|level| *level <= self.max_level)
+            && self.badge_uri_base().is_some()
+            && self.level_up_cooldown >= 0
+            && self.strike_probation_secs >= 0
+    }
 
-    // Требуемые аккаунты: пользователь, чей уровень обновляем, и его PDA
-    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    // Выдаются ли токены за повышение уровня
+    pub fn level_rewards_enabled(&self) -> bool {
+        self.reward_mint != Pubkey::default() && self.reward_per_level > 0
+    }
 
-    // TODO: Добавить проверки аккаунтов (например, что user_pda принадлежит этой программе)
+    // Выдаётся ли бейдж за достижение уровня `level`
+    pub fn is_badge_level(&self, level: u8) -> bool {
+        level != 0 && self.badge_levels.contains(&level)
+    }
 
-    // Десериализуем данные аккаунта PDA
-    let mut account_data = UserAccount::try_from_slice(&user_pda.data.borrow())?;
+    // Можно ли выпускать NFT-бейджи Metaplex
+    pub fn badge_nft_enabled(&self) -> bool {
+        self.badge_collection != Pubkey::default()
+    }
 
-    // TODO: Реализовать логику обновления уровня на основе account_data.karma
-    // Пример очень простой логики:
-    let new_level = (account_data.karma / 1000) as u8; // 1 уровень за каждые 1000 кармы
-    if new_level > account_data.level {
-        account_data.level = new_level;
-        msg!("Level updated to {}", account_data.level);
-        
-        // Сериализуем обновленные данные обратно
-         BorshSerialize::serialize(&account_data, &mut &mut user_pda.data.borrow_mut())?;
-         msg!("User level updated successfully");
-    } else {
-        msg!("Level not changed. Current level: {}, required for next: {}", account_data.level, (account_data.level as u64 + 1) * 1000);
+    // Начало URI метаданных NFT-бейджа без дополняющих нулей (None — не UTF-8)
+    pub fn badge_uri_base(&self) -> Option<&str> {
+        let len = self.badge_uri_base.iter().position(|byte| *byte == 0).unwrap_or(MAX_BADGE_URI_LEN);
+        std::str::from_utf8(&self.badge_uri_base[..len]).ok()
     }
 
-    msg!("UpdateLevel instruction processed successfully");
+    // URI метаданных NFT-бейджа для уровня `level`: <badge_uri_base><level>.json
+    pub fn badge_uri(&self, level: u8) -> String {
+        format!("{}{}.json", self.badge_uri_base().unwrap_or_default(), level)
+    }
 
+    // Число заполненных слотов grant_cosigners
+    pub fn grant_cosigner_count(&self) -> usize {
+        self.grant_cosigners.iter().filter(|key| **key != Pubkey::default()).count()
+    }
 
-    Ok(()) // Успешное выполнение инструкции
+    // Может ли `key` одобрять многоподписные начисления
+    pub fn is_grant_cosigner(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.grant_cosigners.contains(key)
+    }
+
+    // Комиссия с перевода `amount` кармы
+    pub fn transfer_fee(&self, amount: u64) -> u64 {
+        (amount as u128 * self.transfer_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+// Глобальная конфигурация программы (PDA с сидом [b"config"]).
+// Параметры идут последними, а новые параметры добавляются только в конец ConfigParams:
+// данные старой схемы — начало текущего формата, и MigrateConfig дополняет их через realloc.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConfigAccount {
+    pub version: u8, // Версия схемы (CONFIG_ACCOUNT_SCHEMA_VERSION); старые конфигурации обновляет MigrateConfig
+    pub admin: Pubkey,        // Администратор: меняет конфигурацию и начисляет карму
+    pub moderation_log_len: u64, // Количество записей в журнале модерации (индекс следующей записи)
+    pub pending_authority: Option<Pubkey>, // Предложенный новый администратор (ждёт AcceptAuthority)
+    pub paused: bool, // Аварийная пауза: изменяющие карму инструкции отклоняются
+    pub params: ConfigParams, // Настраиваемые параметры
+}
+
+impl VertaAccount for ConfigAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:conf";
+}
+
+// Pubkey = 32 байта, u64 = 8 байт, Option<Pubkey> = 1 + 32 байта (место резервируется и для None), bool = 1 байт,
+// ConfigParams = 1 + 8 + 8 + 8 + 2 + 1 + 8 + 8 + 32 * 5 + 1 + 1 + 8 + 32 + 8 + 3 + 32 + 64 + 8 + 8 + 8 + 1 + 8 + 1 + 8 = 395 байт.
+// Общий размер: 8 (дискриминатор) + 1 + 32 + 8 + 33 + 1 + 395 = 478 байт.
+impl ConfigAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN
+        + 1
+        + 32
+        + 8
+        + 1
+        + 32
+        + 1
+        + (1 + 8 + 8 + 8 + 2 + 1 + 8 + 8 + 32 * MAX_GRANT_COSIGNERS + 1 + 1 + 8 + 32 + 8 + MAX_BADGE_LEVELS + 32 + MAX_BADGE_URI_LEN + 8 + 8 + 8 + 1 + 8 + 1 + 8);
+
+    pub const VERSION_OFFSET: usize = DISCRIMINATOR_LEN;
+
+    // Заполняет параметры, которых не было в схеме `version` (данные старой схемы дополнены нулями)
+    fn upgrade_from(&mut self, version: u8) {
+        if version < 20 {
+            self.params.endorsement_epoch_budget = DEFAULT_ENDORSEMENT_EPOCH_BUDGET;
+        }
+        if version < 21 {
+            self.params.strike_freeze_threshold = DEFAULT_STRIKE_FREEZE_THRESHOLD;
+            self.params.strike_probation_secs = DEFAULT_STRIKE_PROBATION_SECS;
+        }
+        self.version = CONFIG_ACCOUNT_SCHEMA_VERSION;
+    }
+
+    // Кривая уровней (параметры проверяются при записи конфигурации)
+    pub fn level_curve(&self) -> LevelCurve {
+        self.params.level_curve().unwrap_or_default()
+    }
+
+    // Уровень, который покрывает карма `karma` (не выше max_level)
+    pub fn level_for_karma(&self, karma: u64) -> u8 {
+        self.level_curve().level_for_karma(karma).min(self.params.max_level)
+    }
+}
+
+// Длины конфигурации старых схем. Схема 18 — последний формат без поля version: параметры
+// (тогда CONFIG_PARAMS_V18_LEN байт) шли сразу после admin, за ними moderation_log_len,
+// pending_authority и paused. С 19 параметры последние: 20 — без параметров предупреждений,
+// 19 — ещё и без endorsement_epoch_budget.
+pub const CONFIG_PARAMS_V18_LEN: usize =
+    1 + 8 + 8 + 8 + 2 + 1 + 8 + 8 + 32 * MAX_GRANT_COSIGNERS + 1 + 1 + 8 + 32 + 8 + MAX_BADGE_LEVELS + 32 + MAX_BADGE_URI_LEN + 8 + 8 + 8 + 1;
+pub const CONFIG_ACCOUNT_V20_LEN: usize = ConfigAccount::LEN - 9;
+pub const CONFIG_ACCOUNT_V19_LEN: usize = CONFIG_ACCOUNT_V20_LEN - 8;
+pub const CONFIG_ACCOUNT_V18_LEN: usize = CONFIG_ACCOUNT_V19_LEN - 1;
+
+// Поля конфигурации схемы 18 после параметров
+#[derive(BorshDeserialize)]
+struct ConfigV18Tail {
+    moderation_log_len: u64,
+    pending_authority: Option<Pubkey>,
+    paused: bool,
+}
+
+// Декодирует конфигурацию схемы 18 (без поля version, параметры в середине аккаунта)
+fn decode_config_v18(data: &[u8]) -> Result<ConfigAccount, ProgramError> {
+    if data.len() != CONFIG_ACCOUNT_V18_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let admin_end = DISCRIMINATOR_LEN + 32;
+    let params_end = admin_end + CONFIG_PARAMS_V18_LEN;
+    let admin = Pubkey::try_from(&data[DISCRIMINATOR_LEN..admin_end]).map_err(|_| ProgramError::InvalidAccountData)?;
+    // Параметров, добавленных позже, в схеме 18 нет: дополняем нулями
+    let mut params = data[admin_end..params_end].to_vec();
+    params.resize(ConfigAccount::LEN, 0);
+    let params = ConfigParams::deserialize(&mut params.as_slice())?;
+    let tail = ConfigV18Tail::deserialize(&mut &data[params_end..])?;
+    Ok(ConfigAccount {
+        version: 18,
+        admin,
+        moderation_log_len: tail.moderation_log_len,
+        pending_authority: tail.pending_authority,
+        paused: tail.paused,
+        params,
+    })
+}
+
+// Длина данных конфигурации схемы `version` с полем version (None — неизвестная схема).
+// Новые параметры добавляются только в конец, поэтому старые схемы отличаются длиной.
+pub fn config_account_len(version: u8) -> Option<usize> {
+    match version {
+        19 => Some(CONFIG_ACCOUNT_V19_LEN),
+        20 => Some(CONFIG_ACCOUNT_V20_LEN),
+        CONFIG_ACCOUNT_SCHEMA_VERSION => Some(ConfigAccount::LEN),
+        _ => None,
+    }
+}
+
+// Читает конфигурацию любой поддерживаемой схемы и обновляет её до текущей.
+// None — конфигурация уже в текущей схеме.
+fn upgrade_config_data(data: &[u8]) -> Result<Option<ConfigAccount>, ProgramError> {
+    if !ConfigAccount::has_discriminator(data) {
+        return Err(VertaError::InvalidAccountDiscriminator.into());
+    }
+    // До версии 19 поля version не было: такую конфигурацию определяем по длине
+    if data.len() == CONFIG_ACCOUNT_V18_LEN {
+        let mut config = decode_config_v18(data)?;
+        config.upgrade_from(18);
+        return Ok(Some(config));
+    }
+    let version = data.get(ConfigAccount::VERSION_OFFSET).copied().unwrap_or(0);
+    if version == CONFIG_ACCOUNT_SCHEMA_VERSION && data.len() >= ConfigAccount::LEN {
+        return Ok(None);
+    }
+    if config_account_len(version) != Some(data.len()) {
+        msg!("Config account has an unsupported layout: {} bytes", data.len());
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Новые параметры дополняем нулями, затем заполняем по правилам схемы
+    let mut padded = data.to_vec();
+    padded.resize(ConfigAccount::LEN, 0);
+    let mut config = ConfigAccount::try_from_account_data(&padded)?;
+    config.upgrade_from(version);
+    Ok(Some(config))
+}
+
+// Реестр и учёт начислений верификатора (PDA с сидами [b"verifier", verifier]).
+// Администратор регистрирует верификатора с весом (AddVerifier) и снимает регистрацию
+// (RemoveVerifier); учёт ограничивает карму, начисляемую одним верификатором за эпоху.
+// Для администратора и верификаторов по уровню PDA создаётся при первом начислении.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VerifierAccount {
+    pub verifier: Pubkey,      // Верификатор
+    pub epoch: u64,            // Эпоха, к которой относится granted_in_epoch
+    pub granted_in_epoch: u64, // Карма, начисленная в эпохе epoch
+    pub total_granted: u64,    // Карма, начисленная за всё время
+    pub registered: bool,      // Зарегистрирован администратором (AddVerifier)
+    pub weight_bps: u16,       // Множитель начислений в базисных пунктах (BPS_DENOMINATOR = 1x)
+}
+
+// Максимальный вес верификатора: начисление не больше чем в 5 раз от запрошенного
+pub const MAX_VERIFIER_WEIGHT_BPS: u16 = 50_000;
+
+impl VertaAccount for VerifierAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:vrfr";
+}
+
+// Общий размер: 8 (дискриминатор) + 32 + 8 + 8 + 8 + 1 + 2 = 67 байт.
+impl VerifierAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 32 + 8 + 8 + 8 + 1 + 2;
+}
+
+// Поручительство одного пользователя за другого (PDA с сидами [b"endorsement", endorser, endorsee]).
+// Одна запись на пару: повторные поручительства накапливаются в ней, и каждое следующее
+// приносит меньше кармы (вес делится на порядковый номер поручительства).
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EndorsementAccount {
+    pub endorser: Pubkey,     // Кто поручился
+    pub endorsee: Pubkey,     // За кого поручились
+    pub weight: u64,          // Суммарный заявленный вес поручительств
+    pub karma_awarded: u64,   // Сколько кармы всего получил endorsee (меньше веса при повторах и взаимном поручительстве)
+    pub mutual: bool,         // На момент последнего поручительства уже было встречное
+    pub endorsed_at: i64,     // Время последнего поручительства (unix timestamp)
+    pub count: u32,           // Число поручительств в паре (0 в записях до его появления — одно поручительство)
+}
+
+impl VertaAccount for EndorsementAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:endr";
+
+    // Записи без count (ENDORSEMENT_ACCOUNT_V1_LEN байт) читаются с count = 0
+    fn try_from_account_data(data: &[u8]) -> std::io::Result<Self> {
+        if data.len() == ENDORSEMENT_ACCOUNT_V1_LEN {
+            let mut padded = data.to_vec();
+            padded.resize(Self::LEN, 0);
+            return Self::try_from_account_data(&padded);
+        }
+        if !Self::has_discriminator(data) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "account discriminator mismatch",
+            ));
+        }
+        Self::deserialize(&mut &data[DISCRIMINATOR_LEN..])
+    }
 }
 
-// TODO: Добавить другие функции-обработчики по мере необходимости (например, process_verify_contribution)ы
\ No newline at end of file
+// Общий размер: 8 (дискриминатор) + 32 + 32 + 8 + 8 + 1 + 8 + 4 = 101 байт.
+impl EndorsementAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 32 + 32 + 8 + 8 + 1 + 8 + 4;
+
+    // Сколько поручительств учтено в записи
+    pub fn endorsements(&self) -> u32 {
+        self.count.max(1)
+    }
+}
+
+// Размер записи о поручительстве до появления count
+pub const ENDORSEMENT_ACCOUNT_V1_LEN: usize = EndorsementAccount::LEN - 4;
+
+// Карма за поручительство весом `weight`, если оно `count`-е в паре: повторы затухают,
+// встречное поручительство делится на MUTUAL_ENDORSEMENT_DIVISOR
+pub fn endorsement_karma(weight: u64, count: u32, mutual: bool) -> u64 {
+    let karma = weight / u64::from(count.max(1));
+    if mutual {
+        karma / MUTUAL_ENDORSEMENT_DIVISOR
+    } else {
+        karma
+    }
+}
+
+// Максимальный вес одного поручительства
+pub const MAX_ENDORSEMENT_WEIGHT: u64 = 100;
+// Минимальный уровень поручителя: новые аккаунты не могут раздавать карму друг другу
+pub const MIN_ENDORSER_LEVEL: u8 = 1;
+// Во сколько раз уменьшается карма за встречное поручительство
+pub const MUTUAL_ENDORSEMENT_DIVISOR: u64 = 4;
+// Суммарный вес поручительств одного пользователя за эпоху по умолчанию
+pub const DEFAULT_ENDORSEMENT_EPOCH_BUDGET: u64 = 3 * MAX_ENDORSEMENT_WEIGHT;
+
+// Действия модератора в ModerationLogAccount::action
+pub const MODERATION_ACTION_SLASH: u8 = 0;
+pub const MODERATION_ACTION_FREEZE: u8 = 1;
+pub const MODERATION_ACTION_UNFREEZE: u8 = 2;
+pub const MODERATION_ACTION_STRIKE: u8 = 3; // amount — число действующих предупреждений после этого
+pub const MODERATION_ACTION_STRIKE_FREEZE: u8 = 4; // Предупреждение, после которого аккаунт заморожен автоматически
+
+// Параметры предупреждений по умолчанию: третье предупреждение замораживает аккаунт,
+// предупреждения сгорают после 90 дней без новых
+pub const DEFAULT_STRIKE_FREEZE_THRESHOLD: u8 = 3;
+pub const DEFAULT_STRIKE_PROBATION_SECS: i64 = 90 * 24 * 60 * 60;
+
+// Запись журнала модерации (PDA с сидами [b"modlog", index]).
+// Записи только добавляются: по ним можно проверить действия модераторов on-chain.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModerationLogAccount {
+    pub index: u64,             // Порядковый номер записи
+    pub actor: Pubkey,          // Модератор, выполнивший действие
+    pub target: Pubkey,         // Кошелёк пользователя, к которому применено действие
+    pub action: u8,             // MODERATION_ACTION_*
+    pub reason_code: u16,       // Код причины
+    pub reason_hash: [u8; 32],  // Хеш подробного обоснования (хранится off-chain)
+    pub amount: u64,            // Величина действия (например, списанная карма)
+    pub timestamp: i64,         // Время действия (unix timestamp)
+}
+
+impl VertaAccount for ModerationLogAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:mlog";
+}
+
+// Общий размер: 8 (дискриминатор) + 8 + 32 + 32 + 1 + 2 + 32 + 8 + 8 = 131 байт.
+impl ModerationLogAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 8 + 32 + 32 + 1 + 2 + 32 + 8 + 8;
+}
+
+// Казна программы (PDA с сидом [b"treasury"]): копит комиссии за переводы кармы
+// и (в лампортах на балансе аккаунта) платы за регистрацию
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TreasuryAccount {
+    pub karma: u64, // Накопленная карма
+}
+
+impl VertaAccount for TreasuryAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:trsy";
+}
+
+impl TreasuryAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 8;
+}
+
+// Глобальная статистика программы (PDA с сидом [b"stats"]).
+// Создаётся первой инструкцией, которая её обновляет. Дашборды читают агрегаты
+// одним запросом вместо сканирования всех аккаунтов пользователей.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GlobalStatsAccount {
+    pub total_users: u64,                 // Зарегистрированные пользователи (закрытые аккаунты вычитаются)
+    pub registration_fees_collected: u64, // Лампорты, полученные казной за регистрации
+    pub treasury_withdrawn: u64,          // Лампорты, выведенные из казны администратором
+    pub treasury_lamports: u64,           // Баланс казны на момент последнего изменения
+    pub total_karma: u64,                 // Сумма кармы пользователей вместе с overflow_karma (все начисления и списания)
+    pub highest_level: u8,                // Наибольший уровень, до которого поднялся пользователь (UpdateLevel)
+}
+
+impl VertaAccount for GlobalStatsAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:stat";
+}
+
+// Общий размер: 8 (дискриминатор) + 8 + 8 + 8 + 8 + 8 + 1 = 49 байт.
+impl GlobalStatsAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+// Таблица кодов причин начисления кармы (PDA с сидом [b"reasons"]).
+// Ведётся администратором (SetReasonCodes). AddKarma принимает только коды из таблицы,
+// поэтому аналитика разбивает карму по причинам без произвольных строк в блокчейне.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReasonCodeTableAccount {
+    pub codes: [u16; MAX_REASON_CODES], // Разрешённые коды (REASON_NONE — пустой слот)
+}
+
+impl VertaAccount for ReasonCodeTableAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:rsns";
+}
+
+// Общий размер: 8 (дискриминатор) + 2 * 32 = 72 байта.
+impl ReasonCodeTableAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 2 * MAX_REASON_CODES;
+
+    // Есть ли код `reason` в таблице
+    pub fn contains(&self, reason: u16) -> bool {
+        reason != REASON_NONE && self.codes.contains(&reason)
+    }
+}
+
+// Максимальное число кодов в таблице причин
+pub const MAX_REASON_CODES: usize = 32;
+
+// Код «без причины»: допустим всегда и не требует таблицы
+pub const REASON_NONE: u16 = 0;
+
+// Индекс шарда пользователей (PDA с сидами [b"shard", shard]).
+// Шард пользователя — первый байт кошелька владельца (см. user_shard): он хранится
+// в UserAccount по смещению OWNER_OFFSET, поэтому индексеры и кранки выбирают
+// пользователей одного шарда memcmp-фильтром, не сканируя все аккаунты программы.
+// В сиды PDA пользователя шард не входит: адреса [b"user", owner] не меняются.
+// Создаётся первой регистрацией в шарде.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ShardIndexAccount {
+    pub shard: u8,               // Номер шарда
+    pub user_count: u64,         // Регистрации в шарде (закрытия не вычитаются)
+    pub last_registered_at: i64, // Время последней регистрации в шарде (unix timestamp)
+}
+
+impl VertaAccount for ShardIndexAccount {
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN] = *b"vrt:shrd";
+}
+
+// Общий размер: 8 (дискриминатор) + 1 + 8 + 8 = 25 байт.
+impl ShardIndexAccount {
+    pub const LEN: usize = DISCRIMINATOR_LEN + 1 + 8 + 8;
+}
+
+// Число шардов пользователей: по одному на значение первого байта кошелька
+pub const USER_SHARD_COUNT: usize = 256;
+
+// Шард, к которому относится пользователь с кошельком `owner`
+pub fn user_shard(owner: &Pubkey) -> u8 {
+    owner.as_ref()[0]
+}
+
+// Версии схем аккаунтов. Увеличиваются при любом изменении сериализованного формата.
+pub const USER_ACCOUNT_SCHEMA_VERSION: u8 = 14;
+pub const CONTRIBUTION_ACCOUNT_SCHEMA_VERSION: u8 = 2;
+pub const CONFIG_ACCOUNT_SCHEMA_VERSION: u8 = 21;
+
+// Биты маски возможностей в ProgramInfo::features
+pub const FEATURE_CONTRIBUTIONS: u64 = 1 << 0; // VerifyContribution и записи о вкладах
+pub const FEATURE_TOP_UP: u64 = 1 << 1; // TopUpAccount
+pub const FEATURE_CONFIG: u64 = 1 << 2; // Глобальная конфигурация и настраиваемая кривая уровней
+pub const FEATURE_CU_TELEMETRY: u64 = 1 << 3; // Сборка с фичей cu-telemetry
+pub const FEATURE_KARMA_TRANSFER: u64 = 1 << 4; // TransferKarma и казна
+pub const FEATURE_CUSTOM_HEAP: u64 = 1 << 5; // Сборка с фичей custom-heap: транзакциям нужен RequestHeapFrame
+pub const FEATURE_KARMA_DECAY: u64 = 1 << 6; // DecayKarma и период полураспада в конфигурации
+pub const FEATURE_VERIFIER_EPOCH_CAP: u64 = 1 << 7; // Лимит начислений верификатора за эпоху
+pub const FEATURE_GRANT_APPROVALS: u64 = 1 << 8; // AddKarmaWithApprovals
+pub const FEATURE_ACCOUNT_MIGRATION: u64 = 1 << 9; // MigrateAccount
+pub const FEATURE_ENDORSEMENTS: u64 = 1 << 10; // Endorse
+pub const FEATURE_REGISTRATION_FEE: u64 = 1 << 11; // Плата за регистрацию, WithdrawTreasury и GlobalStats
+pub const FEATURE_AUTHORITY_TRANSFER: u64 = 1 << 12; // ProposeAuthority и AcceptAuthority
+pub const FEATURE_PAUSE: u64 = 1 << 13; // SetPaused и аварийная пауза
+pub const FEATURE_FREEZE: u64 = 1 << 14; // FreezeUser и UnfreezeUser
+pub const FEATURE_LEVEL_REWARDS: u64 = 1 << 15; // Минт токенов награды при повышении уровня
+pub const FEATURE_BADGES: u64 = 1 << 16; // InitializeBadgeMint и бейджи Token-2022 за уровни
+pub const FEATURE_BADGE_NFT: u64 = 1 << 17; // MintBadgeNft и обновление метаданных NFT при повышении уровня
+pub const FEATURE_USER_SHARDS: u64 = 1 << 18; // Индексы шардов пользователей (ShardIndexAccount)
+pub const FEATURE_REASON_CODES: u64 = 1 << 19; // Коды причин в AddKarma и SetReasonCodes
+pub const FEATURE_VERIFIER_REGISTRY: u64 = 1 << 20; // AddVerifier, RemoveVerifier и веса верификаторов
+pub const FEATURE_CHALLENGES: u64 = 1 << 21; // ChallengeContribution и ResolveChallenge
+pub const FEATURE_CONFIG_MIGRATION: u64 = 1 << 22; // MigrateConfig и версия схемы в конфигурации
+pub const FEATURE_STRIKES: u64 = 1 << 23; // IssueStrike и автоматическая заморозка по предупреждениям
+
+// Ответ инструкции GetProgramInfo (передаётся через return data)
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramInfo {
+    pub version_major: u16, // Семантическая версия программы (из Cargo.toml)
+    pub version_minor: u16,
+    pub version_patch: u16,
+    pub user_account_schema: u8,         // USER_ACCOUNT_SCHEMA_VERSION
+    pub contribution_account_schema: u8, // CONTRIBUTION_ACCOUNT_SCHEMA_VERSION
+    pub config_account_schema: u8,       // CONFIG_ACCOUNT_SCHEMA_VERSION
+    pub features: u64,                   // Маска FEATURE_*
+}
+
+impl ProgramInfo {
+    // Размер в Borsh: 2 + 2 + 2 + 1 + 1 + 1 + 8 = 17 байт (без выравнивания, в отличие от size_of)
+    pub const LEN: usize = 2 + 2 + 2 + 1 + 1 + 1 + 8;
+
+    // Сведения о текущей сборке программы
+    pub fn current() -> Self {
+        let mut features =
+            FEATURE_CONTRIBUTIONS | FEATURE_TOP_UP | FEATURE_CONFIG | FEATURE_KARMA_TRANSFER
+            | FEATURE_KARMA_DECAY
+            | FEATURE_VERIFIER_EPOCH_CAP
+            | FEATURE_GRANT_APPROVALS
+            | FEATURE_ACCOUNT_MIGRATION
+            | FEATURE_ENDORSEMENTS
+            | FEATURE_REGISTRATION_FEE
+            | FEATURE_AUTHORITY_TRANSFER
+            | FEATURE_PAUSE
+            | FEATURE_FREEZE
+            | FEATURE_LEVEL_REWARDS
+            | FEATURE_BADGES
+            | FEATURE_BADGE_NFT
+            | FEATURE_USER_SHARDS
+            | FEATURE_REASON_CODES
+            | FEATURE_VERIFIER_REGISTRY
+            | FEATURE_CHALLENGES
+            | FEATURE_CONFIG_MIGRATION
+            | FEATURE_STRIKES;
+        if cfg!(feature = "cu-telemetry") {
+            features |= FEATURE_CU_TELEMETRY;
+        }
+        if cfg!(feature = "custom-heap") {
+            features |= FEATURE_CUSTOM_HEAP;
+        }
+        Self {
+            version_major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap_or(0),
+            version_minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap_or(0),
+            version_patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap_or(0),
+            user_account_schema: USER_ACCOUNT_SCHEMA_VERSION,
+            contribution_account_schema: CONTRIBUTION_ACCOUNT_SCHEMA_VERSION,
+            config_account_schema: CONFIG_ACCOUNT_SCHEMA_VERSION,
+            features,
+        }
+    }
+
+    pub fn has_feature(&self, feature: u64) -> bool {
+        self.features & feature == feature
+    }
+}
+
+// Одобрение многоподписного начисления: ed25519-подпись ключа из grant_cosigners
+// над Borsh-сериализованным GrantMessage
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrantApproval {
+    pub signer: Pubkey,
+    pub signature: [u8; 64],
+}
+
+// Набор одобрений, передаваемый в AddKarmaWithApprovals
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Eq)]
+pub struct GrantEnvelope {
+    pub expires_at: i64, // После этого момента (unix timestamp) одобрения недействительны
+    pub approvals: Vec<GrantApproval>,
+}
+
+// Параметры начисления, которые подписывают одобряющие
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrantMessage {
+    pub program_id: Pubkey, // Программа Verta (одобрения не переносятся между деплоями)
+    pub user: Pubkey,       // Кошелёк получателя
+    pub amount: u64,
+    pub category: u8,
+    pub nonce: u64,         // UserAccount::grant_nonce на момент начисления
+    pub expires_at: i64,    // GrantEnvelope::expires_at
+}
+
+// Запас места, под который RegisterUser дополнительно вносит ренту: будущие realloc
+// аккаунта не должны опускать его баланс ниже порога освобождения от ренты
+pub const RENT_HEADROOM_BYTES: usize = 64;
+
+// Минимальное время между двумя повышениями уровня по умолчанию (в секундах, см.
+// ConfigParams::level_up_cooldown). Не даёт одному крупному начислению поднять новый
+// аккаунт сразу на много уровней.
+pub const LEVEL_UP_COOLDOWN: i64 = 24 * 60 * 60;
+
+// Определение возможных инструкций для нашей программы
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Eq)]
+pub enum VertaInstruction {
+    /// Инструкция 0: Регистрация пользователя.
+    /// Создает или инициализирует аккаунт пользователя (PDA).
+    /// Если в конфигурации задана registration_fee_lamports, пользователь платит её в казну.
+    /// Регистрация учитывается в индексе шарда пользователя (ShardIndexAccount).
+    /// Ренту и плату вносит пользователь или спонсор, переданный необязательным последним
+    /// аккаунтом (подписывает); пользователь подписывает в любом случае.
+    RegisterUser,
+
+    /// Инструкция 1: Добавить карму.
+    /// Добавляет указанное количество кармы пользователю.
+    /// Подписать должен администратор из конфигурации, зарегистрированный верификатор
+    /// (AddVerifier; начисление умножается на его вес) или пользователь с уровнем
+    /// не ниже min_verifier_level (тогда последним передаётся его PDA); размер начисления
+    /// ограничен max_karma_per_grant, а сумма начислений верификатора за эпоху —
+    /// verifier_epoch_cap. Карма учитывается в общей сумме, в категории `category` и в GlobalStats.
+    /// `reason` — код причины из таблицы причин (REASON_NONE — без причины), попадает в KarmaAdded.
+    /// Data: [1 (instruction_type), amount: u64, category: u8, reason: u16]
+    AddKarma { amount: u64, category: u8, reason: u16 },
+
+    /// Инструкция 2: Обновить уровень.
+    /// Пересчитывает уровень пользователя на основе текущей кармы.
+    /// За один раз уровень растёт не больше чем на 1 и не чаще раза в level_up_cooldown из конфигурации
+    /// (во время кулдауна возвращается VertaError::CooldownActive);
+    /// остаток кармы засчитывается в следующие уровни. Новый уровень учитывается в GlobalStats.
+    /// Если в конфигурации включены награды (reward_mint, reward_per_level), за уровень выше
+    /// rewarded_level на ATA владельца минтится reward_per_level токенов; mint authority — PDA
+    /// [b"mint_authority"]. Уровни, достигнутые повторно после понижения, не награждаются.
+    /// Тогда после PDA глобальной статистики передаются mint, ATA пользователя, PDA mint authority и SPL Token.
+    /// Если новый уровень есть в badge_levels и бейдж за него ещё не выдавался (badges_awarded),
+    /// пользователь получает бейдж: дальше передаются
+    /// mint бейджа (PDA [b"badge", level]), ATA Token-2022 пользователя, PDA mint authority и Token-2022.
+    /// Если у пользователя есть NFT-бейдж (MintBadgeNft), последними передаются его mint,
+    /// метаданные, PDA mint authority и Token Metadata: URI метаданных меняется на новый уровень.
+    /// Data: [2 (instruction_type)]
+    UpdateLevel,
+
+    /// Инструкция 3: Привязать внешнюю идентичность.
+    /// Сохраняет солёный хеш идентификатора пользователя на внешней платформе,
+    /// чтобы сервисы могли проверить связь аккаунтов без хранения персональных данных.
+    /// Менять привязку может только сам пользователь.
+    /// Data: [3 (instruction_type), platform_id_hash: [u8; 32]]
+    BindExternalIdentity { platform_id_hash: [u8; 32] },
+
+    /// Инструкция 4: Подтвердить вклад другого пользователя.
+    /// Создает PDA записи о вкладе и начисляет за него карму автору.
+    /// Повторно подтвердить тот же вклад нельзя. Права верификатора — как у AddKarma.
+    /// Data: [4 (instruction_type), user_to_verify: Pubkey, contribution_id: u64, karma_awarded: u64]
+    VerifyContribution {
+        user_to_verify: Pubkey,
+        contribution_id: u64,
+        karma_awarded: u64,
+    },
+
+    /// Инструкция 5: Пополнить баланс PDA пользователя.
+    /// Любой может перевести лампорты на аккаунт пользователя, чтобы он оставался
+    /// освобождённым от ренты.
+    /// Data: [5 (instruction_type), lamports: u64]
+    TopUpAccount { lamports: u64 },
+
+    /// Инструкция 6: Создать глобальную конфигурацию программы.
+    /// Подписать должен upgrade authority программы; он же назначает администратора.
+    /// Data: [6 (instruction_type), admin: Pubkey, params: ConfigParams]
+    InitializeConfig { admin: Pubkey, params: ConfigParams },
+
+    /// Инструкция 7: Изменить параметры конфигурации (только администратор).
+    /// Data: [7 (instruction_type), params: ConfigParams]
+    UpdateConfig { params: ConfigParams },
+
+    /// Инструкция 8: Вернуть версию программы, версии схем аккаунтов и маску возможностей.
+    /// Аккаунты не требуются; ProgramInfo передаётся через return data.
+    /// Data: [8 (instruction_type)]
+    GetProgramInfo,
+
+    /// Инструкция 9: Списать карму пользователя (модерация ошибочных начислений).
+    /// Подписать должен администратор из конфигурации. Сначала списывается overflow_karma,
+    /// затем карма (не ниже нуля); уровень понижается, если карма его больше не покрывает.
+    /// Действие записывается в журнал модерации, списанная карма — в GlobalStats.
+    /// Data: [9 (instruction_type), amount: u64, reason_code: u16, reason_hash: [u8; 32]]
+    SlashKarma {
+        amount: u64,
+        reason_code: u16,
+        reason_hash: [u8; 32],
+    },
+
+    /// Инструкция 10: Перевести карму другому зарегистрированному пользователю.
+    /// Подписывает отправитель. С перевода удерживается комиссия из конфигурации:
+    /// она сжигается или зачисляется в казну. Уровень отправителя понижается,
+    /// если оставшаяся карма его не покрывает.
+    /// Data: [10 (instruction_type), amount: u64]
+    TransferKarma { amount: u64 },
+
+    /// Инструкция 11: Закрыть аккаунт пользователя и вернуть ренту.
+    /// Подписать должен владелец аккаунта; замороженный аккаунт (VertaError::AccountFrozen)
+    /// и аккаунт с неразрешёнными спорами по вкладам (VertaError::ChallengeOpen) закрыть нельзя.
+    /// Аккаунт, за уровни которого уже выданы награды или бейджи, тоже закрыть нельзя
+    /// (VertaError::RewardsClaimed): иначе после повторной регистрации их можно было бы получить снова.
+    /// Рента возвращается тому, кто вносил её при регистрации (UserAccount::rent_payer): если это
+    /// спонсор, его кошелёк передаётся необязательным последним аккаунтом. Данные обнуляются, а аккаунт
+    /// возвращается системной программе, поэтому восстановить его со старой кармой нельзя.
+    /// Data: [11 (instruction_type)]
+    CloseUserAccount,
+
+    /// Инструкция 12: Применить затухание кармы (crank, может вызвать кто угодно).
+    /// Карма уменьшается вдвое за каждый karma_half_life секунд, прошедших с last_decay_at
+    /// (начисления и другие изменения аккаунта этот момент не сдвигают).
+    /// Data: [12 (instruction_type)]
+    DecayKarma,
+
+    /// Инструкция 13: Начислить карму по одобрениям нескольких ключей (M из N).
+    /// Одобрения — ed25519-подписи ключей из grant_cosigners над GrantMessage.
+    /// Подписи проверяет нативная программа Ed25519 в той же транзакции;
+    /// инструкция сверяет их с конвертом через sysvar Instructions.
+    /// Data: [13 (instruction_type), amount: u64, category: u8, envelope: GrantEnvelope]
+    AddKarmaWithApprovals {
+        amount: u64,
+        category: u8,
+        envelope: GrantEnvelope,
+    },
+
+    /// Инструкция 14: Обновить аккаунт пользователя старой схемы до текущей.
+    /// Аккаунт расширяется через realloc; недостающую ренту вносит подписант.
+    /// Для схем, не хранивших кошелёк владельца, последним аккаунтом передаётся сам кошелёк.
+    /// Вызвать может кто угодно: данные переносятся без изменений.
+    /// Data: [14 (instruction_type)]
+    MigrateAccount,
+
+    /// Инструкция 15: Поручиться за другого зарегистрированного пользователя.
+    /// Подписывает поручитель (уровень не ниже MIN_ENDORSER_LEVEL), он же платит за запись,
+    /// если необязательным последним аккаунтом не передан отдельный плательщик (подписывает).
+    /// Получатель получает `weight` кармы сообщества, делённой на порядковый номер поручительства
+    /// в паре (повторы накапливаются в той же записи); если он уже поручился за поручителя,
+    /// карма дополнительно делится на MUTUAL_ENDORSEMENT_DIVISOR. Суммарный вес поручительств
+    /// одного пользователя за эпоху ограничен endorsement_epoch_budget из конфигурации.
+    /// Data: [15 (instruction_type), weight: u64]
+    Endorse { weight: u64 },
+
+    /// Инструкция 16: Вывести лампорты из казны.
+    /// Подписать должен администратор из конфигурации. На балансе казны остаётся
+    /// минимум для освобождения от ренты; вывод учитывается в GlobalStats.
+    /// Data: [16 (instruction_type), lamports: u64]
+    WithdrawTreasury { lamports: u64 },
+
+    /// Инструкция 17: Предложить нового администратора (первый шаг передачи прав).
+    /// Подписать должен текущий администратор. Права переходят только после
+    /// AcceptAuthority, поэтому ошибка в адресе не лишает программу администратора.
+    /// `None` отменяет предложение.
+    /// Data: [17 (instruction_type), new_authority: Option<Pubkey>]
+    ProposeAuthority { new_authority: Option<Pubkey> },
+
+    /// Инструкция 18: Принять права администратора (второй шаг передачи прав).
+    /// Подписать должен ключ, предложенный в ProposeAuthority.
+    /// Data: [18 (instruction_type)]
+    AcceptAuthority,
+
+    /// Инструкция 19: Включить или снять аварийную паузу.
+    /// Подписать должен администратор из конфигурации. На паузе отклоняются инструкции,
+    /// изменяющие карму (см. VertaInstruction::is_pausable); чтение, закрытие аккаунтов,
+    /// модерация и управление конфигурацией остаются доступны.
+    /// Data: [19 (instruction_type), paused: bool]
+    SetPaused { paused: bool },
+
+    /// Инструкция 20: Заморозить аккаунт пользователя (обратимая альтернатива списанию).
+    /// Подписать должен администратор из конфигурации. Замороженный аккаунт не получает,
+    /// не переводит и не тратит карму и не может быть закрыт. Действие записывается в журнал модерации.
+    /// Data: [20 (instruction_type), reason_code: u16, reason_hash: [u8; 32]]
+    FreezeUser {
+        reason_code: u16,
+        reason_hash: [u8; 32],
+    },
+
+    /// Инструкция 21: Разморозить аккаунт пользователя.
+    /// Подписать должен администратор из конфигурации. Действие записывается в журнал модерации.
+    /// Data: [21 (instruction_type)]
+    UnfreezeUser,
+
+    /// Инструкция 22: Создать mint бейджа за уровень `level`.
+    /// Подписать должен администратор из конфигурации (он же платит ренту). Mint — PDA
+    /// [b"badge", level] в Token-2022 с расширением NonTransferable, без десятичных знаков,
+    /// mint authority — PDA [b"mint_authority"]. Выданный бейдж нельзя передать другому кошельку.
+    /// Data: [22 (instruction_type), level: u8]
+    InitializeBadgeMint { level: u8 },
+
+    /// Инструкция 23: Выпустить NFT-бейдж Metaplex.
+    /// Пользователь подписывает и платит ренту (или отдельный плательщик, переданный
+    /// необязательным последним аккаунтом). Mint NFT — PDA [b"badge_nft", owner], выпускается
+    /// один раз; метаданные указывают на коллекцию badge_collection из конфигурации (без верификации),
+    /// их update authority — PDA [b"mint_authority"], поэтому UpdateLevel может менять URI.
+    /// Data: [23 (instruction_type)]
+    MintBadgeNft,
+
+    /// Инструкция 24: Задать таблицу кодов причин начисления.
+    /// Подписать должен администратор из конфигурации (он же платит за создание таблицы).
+    /// Data: [24 (instruction_type), codes: [u16; MAX_REASON_CODES]]
+    SetReasonCodes { codes: [u16; MAX_REASON_CODES] },
+
+    /// Инструкция 25: Зарегистрировать верификатора или изменить его вес.
+    /// Подписать должен администратор из конфигурации (он же платит за PDA верификатора).
+    /// `weight` — множитель начислений в базисных пунктах (от 1 до MAX_VERIFIER_WEIGHT_BPS).
+    /// Data: [25 (instruction_type), weight: u16]
+    AddVerifier { weight: u16 },
+
+    /// Инструкция 26: Снять регистрацию верификатора.
+    /// Подписать должен администратор из конфигурации. Учёт начислений сохраняется.
+    /// Data: [26 (instruction_type)]
+    RemoveVerifier,
+
+    /// Инструкция 27: Оспорить подтверждённый вклад.
+    /// Подписывает оспаривающий (не автор вклада) и вносит залог challenge_stake_lamports.
+    /// Карма за вклад снимается с автора и блокируется в записи спора до ResolveChallenge;
+    /// до этого автор не может закрыть аккаунт.
+    /// Data: [27 (instruction_type), contribution_id: u64]
+    ChallengeContribution { contribution_id: u64 },
+
+    /// Инструкция 28: Разрешить спор по вкладу.
+    /// Подписать должен администратор из конфигурации. `upheld` = true: карма за вклад
+    /// отзывается, залог возвращается оспаривающему; false: карма возвращается автору,
+    /// залог уходит в казну (учитывается в GlobalStats).
+    /// Data: [28 (instruction_type), upheld: bool]
+    ResolveChallenge { upheld: bool },
+
+    /// Инструкция 29: Обновить глобальную конфигурацию старой схемы до текущей.
+    /// Аккаунт расширяется через realloc, новые параметры получают значения по умолчанию;
+    /// недостающую ренту вносит подписант. Вызвать может кто угодно: параметры не меняются.
+    /// Data: [29 (instruction_type)]
+    MigrateConfig,
+
+    /// Инструкция 30: Вынести пользователю предупреждение.
+    /// Подписать должен администратор из конфигурации. Предупреждения учитываются отдельно от кармы
+    /// и сгорают, если за strike_probation_secs не было новых. Когда действующих предупреждений
+    /// набирается strike_freeze_threshold, аккаунт замораживается; UnfreezeUser предупреждения
+    /// не снимает. Действие записывается в журнал модерации.
+    /// Data: [30 (instruction_type), reason_code: u16, reason_hash: [u8; 32]]
+    IssueStrike {
+        reason_code: u16,
+        reason_hash: [u8; 32],
+    },
+}
+
+impl VertaInstruction {
+    // Отклоняется ли инструкция, пока программа на паузе
+    pub fn is_pausable(&self) -> bool {
+        matches!(
+            self,
+            VertaInstruction::RegisterUser
+                | VertaInstruction::AddKarma { .. }
+                | VertaInstruction::UpdateLevel
+                | VertaInstruction::VerifyContribution { .. }
+                | VertaInstruction::TransferKarma { .. }
+                | VertaInstruction::DecayKarma
+                | VertaInstruction::AddKarmaWithApprovals { .. }
+                | VertaInstruction::Endorse { .. }
+                | VertaInstruction::ChallengeContribution { .. }
+        )
+    }
+}
+
+// Главная точка входа в программу
+entrypoint!(process_instruction);
+
+// Размер кучи в сборке с фичей custom-heap. Транзакция должна запросить такую кучу
+// инструкцией ComputeBudget RequestHeapFrame, иначе доступна только стандартная (32 КБ).
+pub const HEAP_FRAME_BYTES: usize = 256 * 1024;
+
+// С фичей custom-heap entrypoint! не объявляет стандартный аллокатор на 32 КБ:
+// используем тот же bump-аллокатор, но на всю запрошенную кучу
+#[cfg(all(target_os = "solana", feature = "custom-heap"))]
+#[global_allocator]
+static ALLOCATOR: solana_program::entrypoint::BumpAllocator = solana_program::entrypoint::BumpAllocator {
+    start: solana_program::entrypoint::HEAP_START_ADDRESS as usize,
+    len: HEAP_FRAME_BYTES,
+};
+
+// Основная функция обработки инструкций
+fn process_instruction(
+    program_id: &Pubkey,        // ID вашей программы
+    accounts: &[AccountInfo],   // Список аккаунтов, участвующих в транзакции
+    instruction_data: &[u8],    // Данные инструкции (определяют, что делать)
+) -> ProgramResult {
+    msg!("Verta Program Entrypoint"); // Отладочное сообщение в начале
+
+    // Десериализация данных инструкции
+    let instruction = VertaInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    msg!("Received instruction: {:?}", instruction); // Отладочное сообщение с типом инструкции
+
+    // Аварийная пауза. Все приостанавливаемые инструкции получают конфигурацию и проверяют
+    // её адрес сами, поэтому обойти паузу, не передав конфигурацию, нельзя
+    if instruction.is_pausable() && is_paused(program_id, accounts)? {
+        msg!("Program is paused");
+        return Err(VertaError::ProgramPaused.into());
+    }
+
+    // Остаток вычислительных единиц перед обработчиком (только с фичей cu-telemetry)
+    #[cfg(feature = "cu-telemetry")]
+    let compute_units_at_start = sol_remaining_compute_units();
+
+    // Используем match для вызова нужной функции-обработчика
+    let result = match instruction {
+        VertaInstruction::RegisterUser => {
+            msg!("Processing RegisterUser instruction");
+            process_register_user(program_id, accounts)
+        }
+        VertaInstruction::AddKarma { amount, category, reason } => {
+            msg!("Processing AddKarma instruction");
+            process_add_karma(program_id, accounts, amount, category, reason)
+        }
+        VertaInstruction::UpdateLevel => {
+            msg!("Processing UpdateLevel instruction");
+            process_update_level(program_id, accounts)
+        }
+        VertaInstruction::BindExternalIdentity { platform_id_hash } => {
+            msg!("Processing BindExternalIdentity instruction");
+            process_bind_external_identity(program_id, accounts, platform_id_hash)
+        }
+        VertaInstruction::VerifyContribution { user_to_verify, contribution_id, karma_awarded } => {
+            msg!("Processing VerifyContribution instruction");
+            process_verify_contribution(program_id, accounts, user_to_verify, contribution_id, karma_awarded)
+        }
+        VertaInstruction::TopUpAccount { lamports } => {
+            msg!("Processing TopUpAccount instruction");
+            process_top_up_account(program_id, accounts, lamports)
+        }
+        VertaInstruction::InitializeConfig { admin, params } => {
+            msg!("Processing InitializeConfig instruction");
+            process_initialize_config(program_id, accounts, admin, params)
+        }
+        VertaInstruction::UpdateConfig { params } => {
+            msg!("Processing UpdateConfig instruction");
+            process_update_config(program_id, accounts, params)
+        }
+        VertaInstruction::GetProgramInfo => {
+            msg!("Processing GetProgramInfo instruction");
+            process_get_program_info()
+        }
+        VertaInstruction::SlashKarma {
+            amount,
+            reason_code,
+            reason_hash,
+        } => {
+            msg!("Processing SlashKarma instruction");
+            process_slash_karma(program_id, accounts, amount, reason_code, reason_hash)
+        }
+        VertaInstruction::TransferKarma { amount } => {
+            msg!("Processing TransferKarma instruction");
+            process_transfer_karma(program_id, accounts, amount)
+        }
+        VertaInstruction::CloseUserAccount => {
+            msg!("Processing CloseUserAccount instruction");
+            process_close_user_account(program_id, accounts)
+        }
+        VertaInstruction::DecayKarma => {
+            msg!("Processing DecayKarma instruction");
+            process_decay_karma(program_id, accounts)
+        }
+        VertaInstruction::AddKarmaWithApprovals {
+            amount,
+            category,
+            envelope,
+        } => {
+            msg!("Processing AddKarmaWithApprovals instruction");
+            process_add_karma_with_approvals(program_id, accounts, amount, category, envelope)
+        }
+        VertaInstruction::MigrateAccount => {
+            msg!("Processing MigrateAccount instruction");
+            process_migrate_account(program_id, accounts)
+        }
+        VertaInstruction::Endorse { weight } => {
+            msg!("Processing Endorse instruction");
+            process_endorse(program_id, accounts, weight)
+        }
+        VertaInstruction::WithdrawTreasury { lamports } => {
+            msg!("Processing WithdrawTreasury instruction");
+            process_withdraw_treasury(program_id, accounts, lamports)
+        }
+        VertaInstruction::ProposeAuthority { new_authority } => {
+            msg!("Processing ProposeAuthority instruction");
+            process_propose_authority(program_id, accounts, new_authority)
+        }
+        VertaInstruction::AcceptAuthority => {
+            msg!("Processing AcceptAuthority instruction");
+            process_accept_authority(program_id, accounts)
+        }
+        VertaInstruction::SetPaused { paused } => {
+            msg!("Processing SetPaused instruction");
+            process_set_paused(program_id, accounts, paused)
+        }
+        VertaInstruction::FreezeUser {
+            reason_code,
+            reason_hash,
+        } => {
+            msg!("Processing FreezeUser instruction");
+            process_set_user_frozen(program_id, accounts, true, reason_code, reason_hash)
+        }
+        VertaInstruction::UnfreezeUser => {
+            msg!("Processing UnfreezeUser instruction");
+            process_set_user_frozen(program_id, accounts, false, 0, [0; 32])
+        }
+        VertaInstruction::InitializeBadgeMint { level } => {
+            msg!("Processing InitializeBadgeMint instruction");
+            process_initialize_badge_mint(program_id, accounts, level)
+        }
+        VertaInstruction::MintBadgeNft => {
+            msg!("Processing MintBadgeNft instruction");
+            process_mint_badge_nft(program_id, accounts)
+        }
+        VertaInstruction::SetReasonCodes { codes } => {
+            msg!("Processing SetReasonCodes instruction");
+            process_set_reason_codes(program_id, accounts, codes)
+        }
+        VertaInstruction::AddVerifier { weight } => {
+            msg!("Processing AddVerifier instruction");
+            process_add_verifier(program_id, accounts, weight)
+        }
+        VertaInstruction::RemoveVerifier => {
+            msg!("Processing RemoveVerifier instruction");
+            process_remove_verifier(program_id, accounts)
+        }
+        VertaInstruction::ChallengeContribution { contribution_id } => {
+            msg!("Processing ChallengeContribution instruction");
+            process_challenge_contribution(program_id, accounts, contribution_id)
+        }
+        VertaInstruction::ResolveChallenge { upheld } => {
+            msg!("Processing ResolveChallenge instruction");
+            process_resolve_challenge(program_id, accounts, upheld)
+        }
+        VertaInstruction::MigrateConfig => {
+            msg!("Processing MigrateConfig instruction");
+            process_migrate_config(program_id, accounts)
+        }
+        VertaInstruction::IssueStrike {
+            reason_code,
+            reason_hash,
+        } => {
+            msg!("Processing IssueStrike instruction");
+            process_issue_strike(program_id, accounts, reason_code, reason_hash)
+        }
+    };
+
+    // Пишем событие с числом вычислительных единиц, потраченных обработчиком, чтобы
+    // индексатор отслеживал реальное потребление по типам инструкций
+    #[cfg(feature = "cu-telemetry")]
+    event::ComputeUnits {
+        instruction: instruction_data[0],
+        consumed: compute_units_at_start.saturating_sub(sol_remaining_compute_units()),
+    }
+    .emit();
+
+    result
+}
+
+// --- Функции-обработчики инструкций ---
+
+// Обработчик инструкции RegisterUser
+fn process_register_user(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_register_user");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты для этой инструкции
+    let user = next_account_info(accounts_iter)?; // Аккаунт пользователя (подписывает создание)
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт для хранения данных
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунта
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации (может быть не создан)
+    let treasury_pda = next_account_info(accounts_iter)?; // PDA казны, получающей плату за регистрацию
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+    let shard_index_pda = next_account_info(accounts_iter)?; // PDA индекса шарда пользователя
+    let payer = optional_payer(accounts_iter, user)?; // Плательщик ренты и платы за регистрацию
+
+    // Проверки аккаунтов
+    if !user.is_signer {
+        msg!("User account must be a signer for registration");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Проверка PDA
+    let (pda, bump) = Pubkey::find_program_address(&[b"user", user.key.as_ref()], program_id);
+    if pda != *user_pda.key {
+        msg!("Invalid PDA address provided for registration");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    // Проверка, что аккаунт PDA не используется другой программой
+    if user_pda.owner != program_id && !user_pda.data_is_empty() {
+         msg!("Provided PDA account is owned by another program");
+         return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Если аккаунт PDA пустой, создаем его
+    if user_pda.data_is_empty() {
+        msg!("Creating user account (PDA)");
+
+        let space = UserAccount::LEN; // Размер аккаунта
+        // Требуемый баланс для ренты с запасом на будущее расширение аккаунта
+        let rent_required = Rent::get()?.minimum_balance(space + RENT_HEADROOM_BYTES);
+
+        // Инструкция для создания аккаунта через системную программу
+        let create_account_instruction = &system_instruction::create_account(
+            payer.key,         // Отправитель (пользователь или спонсор)
+            user_pda.key,      // Получатель (PDA)
+            rent_required,     // Необходимый баланс для ренты
+            space as u64,      // Размер аккаунта в байтах
+            program_id,        // Владелец аккаунта (наша программа)
+        );
+
+        // Вызов инструкции создания аккаунта с подписью PDA
+        invoke_signed(
+            create_account_instruction,
+            &[payer.clone(), user_pda.clone(), system_program.clone()], // Аккаунты, участвующие в инструкции
+            &[&[b"user", user.key.as_ref(), &[bump]]], // Сиды и бамп для подписи PDA
+        )?;
+
+        // Инициализация данных в новом аккаунте: нулевые карма и уровень
+        let now = Clock::get()?.unix_timestamp;
+        let account_data = UserAccount {
+            version: USER_ACCOUNT_SCHEMA_VERSION,
+            owner: *user.key,
+            bump,
+            registered_at: now,
+            last_updated: now,
+            last_decay_at: now,
+            rent_payer: *payer.key,
+            ..UserAccount::default()
+        };
+        account_data.write_account_data(&mut user_pda.data.borrow_mut())?;
+
+        // Плата за регистрацию (защита от массового создания аккаунтов)
+        let fee = registration_fee(program_id, config_pda)?;
+        if fee > 0 {
+            load_or_create_treasury(program_id, payer, treasury_pda, system_program)?;
+            invoke(
+                &system_instruction::transfer(payer.key, treasury_pda.key, fee),
+                &[payer.clone(), treasury_pda.clone(), system_program.clone()],
+            )?;
+            msg!("Registration fee of {} lamports paid to the treasury", fee);
+        }
+        update_global_stats(program_id, payer, stats_pda, system_program, |stats| {
+            stats.total_users = stats.total_users.saturating_add(1);
+            stats.registration_fees_collected = stats.registration_fees_collected.saturating_add(fee);
+            if fee > 0 {
+                stats.treasury_lamports = treasury_pda.lamports();
+            }
+        })?;
+        update_shard_index(program_id, payer, shard_index_pda, system_program, user_shard(user.key), now)?;
+
+        UserRegistered {
+            user: *user.key,
+            registered_at: now,
+        }
+        .emit();
+
+        msg!("User account created and initialized successfully");
+
+    } else {
+        msg!("User account already exists. Skipping creation.");
+        // Можно добавить логику для повторной инициализации, если нужно
+    }
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции AddKarma
+fn process_add_karma(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    category: u8,
+    reason: u16,
+) -> ProgramResult {
+    msg!("Entering process_add_karma");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let verifier = next_account_info(accounts_iter)?; // Тот, кто добавляет карму (подписывает, платит за учёт)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_to_update_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let verifier_pda = next_account_info(accounts_iter)?; // PDA учёта начислений верификатора
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания учёта
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+    let reasons_pda = next_account_info(accounts_iter)?; // PDA таблицы причин (может быть не создан)
+    let verifier_user_pda = next_account_info(accounts_iter).ok(); // PDA пользователя-верификатора (необязательный)
+
+    // Добавлять карму может только авторизованный верификатор
+    let config = load_config(program_id, config_pda)?;
+    let weight_bps = check_verifier_authority(program_id, &config, verifier, verifier_pda, verifier_user_pda)?;
+    let amount = apply_verifier_weight(amount, weight_bps)?;
+    if reason != REASON_NONE && !load_reason_codes(program_id, reasons_pda)?.contains(reason) {
+        msg!("Reason code {} is not in the reason table", reason);
+        return Err(VertaError::UnknownReasonCode.into());
+    }
+    record_verifier_grant(program_id, &config, verifier, verifier_pda, system_program, amount)?;
+
+    // Десериализуем данные аккаунта PDA
+    let mut account_data = load_user_account(program_id, user_to_update_pda)?;
+    if account_data.owner == *verifier.key && config.admin != *verifier.key {
+        msg!("Verifiers cannot grant karma to themselves");
+        return Err(VertaError::SelfVerification.into());
+    }
+
+    // Обновляем карму (статистику создаёт верификатор, если её ещё нет)
+    create_global_stats(program_id, verifier, stats_pda, system_program)?;
+    credit_karma(program_id, &config, &mut account_data, stats_pda, amount, category, reason)?;
+
+    // Сериализуем обновленные данные обратно в аккаунт
+    store_user_account(user_to_update_pda, &mut account_data)?;
+
+    // TODO: Возможно, здесь же вызывать process_update_level, или сделать это отдельной инструкцией
+
+    msg!("AddKarma instruction processed successfully");
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Необязательный последний аккаунт инструкции — отдельный плательщик ренты (спонсор
+// или релейер). Без него платит `user`. Плательщик должен подписать транзакцию.
+fn optional_payer<'a, 'b>(
+    accounts_iter: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+    user: &'a AccountInfo<'b>,
+) -> Result<&'a AccountInfo<'b>, ProgramError> {
+    let payer = next_account_info(accounts_iter).unwrap_or(user);
+    if !payer.is_signer {
+        msg!("Payer account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(payer)
+}
+
+// Загружает таблицу причин; если она ещё не создана, в ней нет ни одного кода
+fn load_reason_codes(program_id: &Pubkey, reasons_pda: &AccountInfo) -> Result<ReasonCodeTableAccount, ProgramError> {
+    let (expected_reasons_pda, _) = Pubkey::find_program_address(&[b"reasons"], program_id);
+    if expected_reasons_pda != *reasons_pda.key {
+        msg!("Invalid reason table PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if reasons_pda.data_is_empty() {
+        return Ok(ReasonCodeTableAccount::default());
+    }
+    if reasons_pda.owner != program_id {
+        msg!("Reason table PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    ReasonCodeTableAccount::try_from_account_data(&reasons_pda.data.borrow())
+        .map_err(|_| VertaError::InvalidAccountDiscriminator.into())
+}
+
+// Загружает аккаунт пользователя: проверяет регистрацию, владельца PDA, размер данных,
+// дискриминатор и то, что адрес PDA выведен из сохранённого в аккаунте кошелька.
+// Все обработчики читают аккаунты пользователей только через эту функцию.
+fn load_user_account(program_id: &Pubkey, user_pda: &AccountInfo) -> Result<UserAccount, ProgramError> {
+    if user_pda.data_is_empty() {
+        msg!("User account {} is not registered", user_pda.key);
+        return Err(VertaError::AccountNotRegistered.into());
+    }
+    if user_pda.owner != program_id {
+        msg!("User PDA {} is not owned by this program", user_pda.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !UserAccount::has_discriminator(&user_pda.data.borrow()) {
+        // Аккаунты, созданные до появления дискриминаторов, обновляет MigrateAccount
+        if UnversionedUserLayout::from_len(user_pda.data_len()).is_some_and(|layout| !layout.has_discriminator()) {
+            msg!("User account {} uses an outdated layout", user_pda.key);
+            return Err(VertaError::AccountMigrationRequired.into());
+        }
+        msg!("Account {} is not a user account", user_pda.key);
+        return Err(VertaError::InvalidAccountDiscriminator.into());
+    }
+    if user_pda.data_len() < UserAccount::LEN
+        || user_pda.data.borrow()[UserAccount::VERSION_OFFSET] != USER_ACCOUNT_SCHEMA_VERSION
+    {
+        msg!("User account {} uses an outdated layout", user_pda.key);
+        return Err(VertaError::AccountMigrationRequired.into());
+    }
+    let account_data = UserAccount::try_from_account_data(&user_pda.data.borrow())?;
+    let expected_pda =
+        Pubkey::create_program_address(&[b"user", account_data.owner.as_ref(), &[account_data.bump]], program_id)
+            .map_err(|_| VertaError::InvalidPda)?;
+    if expected_pda != *user_pda.key {
+        msg!("User PDA {} does not match its stored owner {}", user_pda.key, account_data.owner);
+        return Err(VertaError::InvalidPda.into());
+    }
+    Ok(account_data)
+}
+
+// Сохраняет аккаунт пользователя, отмечая время изменения
+fn store_user_account(user_pda: &AccountInfo, account_data: &mut UserAccount) -> ProgramResult {
+    account_data.last_updated = Clock::get()?.unix_timestamp;
+    account_data.write_account_data(&mut user_pda.data.borrow_mut())?;
+    Ok(())
+}
+
+// Загружает глобальную конфигурацию, проверяя адрес и владельца PDA
+fn load_config(program_id: &Pubkey, config_pda: &AccountInfo) -> Result<ConfigAccount, ProgramError> {
+    let (expected_config_pda, _) = Pubkey::find_program_address(&[b"config"], program_id);
+    if expected_config_pda != *config_pda.key {
+        msg!("Invalid config PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if config_pda.data_is_empty() {
+        msg!("Program config is not initialized");
+        return Err(VertaError::ConfigNotInitialized.into());
+    }
+    if config_pda.owner != program_id {
+        msg!("Config PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !ConfigAccount::has_discriminator(&config_pda.data.borrow()) {
+        msg!("Account {} is not a config account", config_pda.key);
+        return Err(VertaError::InvalidAccountDiscriminator.into());
+    }
+    if config_pda.data_len() < ConfigAccount::LEN
+        || config_pda.data.borrow()[ConfigAccount::VERSION_OFFSET] != CONFIG_ACCOUNT_SCHEMA_VERSION
+    {
+        msg!("Config account uses an outdated layout; run MigrateConfig");
+        return Err(VertaError::AccountMigrationRequired.into());
+    }
+    Ok(ConfigAccount::try_from_account_data(&config_pda.data.borrow())?)
+}
+
+// Проверяет, что верификатор подписал транзакцию и имеет право начислять карму.
+// Право есть у администратора из конфигурации, а при ненулевом min_verifier_level —
+// у любого пользователя с уровнем не ниже порога. Для этого обработчик передаёт
+// PDA пользователя-верификатора (необязательный последний аккаунт инструкции).
+// Возвращает вес начислений верификатора в базисных пунктах: у зарегистрированного
+// верификатора — вес из реестра, у администратора и верификатора по уровню — 1x.
+fn check_verifier_authority(
+    program_id: &Pubkey,
+    config: &ConfigAccount,
+    verifier: &AccountInfo,
+    verifier_pda: &AccountInfo,
+    verifier_user_pda: Option<&AccountInfo>,
+) -> Result<u16, ProgramError> {
+    if !verifier.is_signer {
+        msg!("Verifier account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if config.admin == *verifier.key {
+        return Ok(BPS_DENOMINATOR as u16);
+    }
+    if let Some(record) = load_verifier_record(program_id, verifier.key, verifier_pda)? {
+        if record.registered {
+            return Ok(record.weight_bps);
+        }
+    }
+    let min_level = config.params.min_verifier_level;
+    if let (true, Some(verifier_user_pda)) = (min_level > 0, verifier_user_pda) {
+        let verifier_data = load_user_account(program_id, verifier_user_pda)?;
+        if verifier_data.owner == *verifier.key && verifier_data.level >= min_level {
+            return Ok(BPS_DENOMINATOR as u16);
+        }
+        msg!("Verifier {} is level {}, level {} is required", verifier.key, verifier_data.level, min_level);
+    }
+    msg!("Signer {} is not an authorized verifier", verifier.key);
+    Err(VertaError::Unauthorized.into())
+}
+
+// Начисление с учётом веса верификатора
+fn apply_verifier_weight(amount: u64, weight_bps: u16) -> Result<u64, ProgramError> {
+    let weighted = amount as u128 * weight_bps as u128 / BPS_DENOMINATOR as u128;
+    u64::try_from(weighted).map_err(|_| VertaError::KarmaOverflow.into())
+}
+
+// Загружает PDA верификатора (None — ещё не создан), проверяя адрес, владельца и тип
+fn load_verifier_record(
+    program_id: &Pubkey,
+    verifier: &Pubkey,
+    verifier_pda: &AccountInfo,
+) -> Result<Option<VerifierAccount>, ProgramError> {
+    let (expected_verifier_pda, _) = Pubkey::find_program_address(&[b"verifier", verifier.as_ref()], program_id);
+    if expected_verifier_pda != *verifier_pda.key {
+        msg!("Invalid verifier PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if verifier_pda.data_is_empty() {
+        return Ok(None);
+    }
+    if verifier_pda.owner != program_id {
+        msg!("Verifier PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !VerifierAccount::has_discriminator(&verifier_pda.data.borrow()) {
+        msg!("Account {} is not a verifier account", verifier_pda.key);
+        return Err(VertaError::InvalidAccountDiscriminator.into());
+    }
+    Ok(Some(VerifierAccount::try_from_account_data(&verifier_pda.data.borrow())?))
+}
+
+// Учитывает начисление в PDA верификатора и проверяет лимит на эпоху.
+// PDA создаётся при первом начислении за счёт верификатора.
+fn record_verifier_grant<'a>(
+    program_id: &Pubkey,
+    config: &ConfigAccount,
+    verifier: &AccountInfo<'a>,
+    verifier_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let mut record = match load_verifier_record(program_id, verifier.key, verifier_pda)? {
+        Some(record) => record,
+        None => {
+            create_verifier_pda(program_id, verifier, verifier.key, verifier_pda, system_program)?;
+            VerifierAccount {
+                verifier: *verifier.key,
+                ..VerifierAccount::default()
+            }
+        }
+    };
+
+    // С новой эпохой счётчик начинается заново
+    let epoch = Clock::get()?.epoch;
+    if record.epoch != epoch {
+        record.epoch = epoch;
+        record.granted_in_epoch = 0;
+    }
+
+    let granted_in_epoch = record.granted_in_epoch.checked_add(amount).ok_or(VertaError::KarmaOverflow)?;
+    let cap = config.params.verifier_epoch_cap;
+    if cap > 0 && granted_in_epoch > cap {
+        msg!(
+            "Verifier epoch cap exceeded: {} granted in epoch {}, cap {}",
+            record.granted_in_epoch,
+            epoch,
+            cap
+        );
+        return Err(VertaError::VerifierEpochCapExceeded.into());
+    }
+    record.granted_in_epoch = granted_in_epoch;
+    record.total_granted = record.total_granted.saturating_add(amount);
+    record.write_account_data(&mut verifier_pda.data.borrow_mut())?;
+
+    Ok(())
+}
+
+// Создаёт PDA верификатора `verifier` за счёт `payer` (адрес уже проверен)
+fn create_verifier_pda<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    verifier: &Pubkey,
+    verifier_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (_, bump) = Pubkey::find_program_address(&[b"verifier", verifier.as_ref()], program_id);
+    let space = VerifierAccount::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(payer.key, verifier_pda.key, rent_required, space as u64, program_id),
+        &[payer.clone(), verifier_pda.clone(), system_program.clone()],
+        &[&[b"verifier", verifier.as_ref(), &[bump]]],
+    )
+}
+
+// Проверяет, что администратор из конфигурации подписал транзакцию
+fn check_admin(config: &ConfigAccount, admin: &AccountInfo) -> ProgramResult {
+    if !admin.is_signer {
+        msg!("Admin account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if config.admin != *admin.key {
+        msg!("Signer {} is not the config admin", admin.key);
+        return Err(VertaError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+// Проверяет, что `authority` подписал транзакцию и является upgrade authority программы,
+// записанным в её ProgramData аккаунте
+fn check_upgrade_authority(
+    program_id: &Pubkey,
+    authority: &AccountInfo,
+    program_data: &AccountInfo,
+) -> ProgramResult {
+    if !authority.is_signer {
+        msg!("Upgrade authority must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_program_data, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if expected_program_data != *program_data.key || *program_data.owner != bpf_loader_upgradeable::id() {
+        msg!("Invalid ProgramData account provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    // Заголовок ProgramData: тег варианта u32 (3), slot u64, Option<Pubkey> (тег u8 + 32 байта)
+    let data = program_data.data.borrow();
+    let upgrade_authority = match data.get(..UpgradeableLoaderState::size_of_programdata_metadata()) {
+        Some(header) if header[0..4] == 3u32.to_le_bytes() && header[12] == 1 => {
+            Pubkey::try_from(&header[13..45]).ok()
+        }
+        _ => None,
+    };
+    if upgrade_authority != Some(*authority.key) {
+        msg!("Signer {} is not the program upgrade authority", authority.key);
+        return Err(VertaError::Unauthorized.into());
+    }
+
+    Ok(())
+}
+
+// Начисление кармы пользователю с проверкой лимитов.
+// Общая логика для AddKarma и VerifyContribution.
+fn credit_karma(
+    program_id: &Pubkey,
+    config: &ConfigAccount,
+    account_data: &mut UserAccount,
+    stats_pda: &AccountInfo,
+    amount: u64,
+    category: u8,
+    reason: u16,
+) -> ProgramResult {
+    if account_data.frozen {
+        msg!("User account {} is frozen", account_data.owner);
+        return Err(VertaError::AccountFrozen.into());
+    }
+    if category as usize >= KARMA_CATEGORIES {
+        msg!("Unknown karma category {}", category);
+        return Err(VertaError::InvalidCategory.into());
+    }
+    if amount > config.params.max_karma_per_grant {
+        msg!("Grant of {} karma exceeds the per-grant maximum of {}", amount, config.params.max_karma_per_grant);
+        return Err(VertaError::GrantTooLarge.into());
+    }
+
+    // Ограничение скорости: сумма кармы за скользящее окно не должна превышать лимит
+    let today = day_index(Clock::get()?.unix_timestamp);
+    advance_window(&mut account_data.karma_window, account_data.window_day, today);
+    account_data.window_day = today;
+    let earned_in_window = window_total(&account_data.karma_window);
+    let velocity_limit = config.params.karma_velocity_limit;
+    if velocity_limit > 0 && earned_in_window.saturating_add(amount) > velocity_limit {
+        msg!(
+            "Karma velocity limit exceeded: {} earned in the last {} days, limit {}",
+            earned_in_window,
+            WINDOW_DAYS,
+            velocity_limit
+        );
+        return Err(VertaError::VelocityLimitExceeded.into());
+    }
+    account_data.karma_window[0] = account_data.karma_window[0]
+        .checked_add(amount)
+        .ok_or(VertaError::KarmaOverflow)?;
+
+    add_category_karma(account_data, category, amount)?;
+    add_capped_karma(program_id, config, account_data, stats_pda, amount)?;
+
+    KarmaAdded {
+        user: account_data.owner,
+        amount,
+        category,
+        karma: account_data.karma,
+        overflow_karma: account_data.overflow_karma,
+        reason,
+    }
+    .emit();
+
+    Ok(())
+}
+
+// Учитывает заработанную карму в категории (категория уже проверена)
+fn add_category_karma(account_data: &mut UserAccount, category: u8, amount: u64) -> ProgramResult {
+    let total = &mut account_data.category_karma[category as usize];
+    *total = total.checked_add(amount).ok_or(VertaError::KarmaOverflow)?;
+    Ok(())
+}
+
+// Зачисляет карму; всё сверх порога максимального уровня уходит в overflow_karma.
+// Зачисленное учитывается в GlobalStatsAccount::total_karma.
+fn add_capped_karma(
+    program_id: &Pubkey,
+    config: &ConfigAccount,
+    account_data: &mut UserAccount,
+    stats_pda: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    // Карма сверх порога максимального уровня не раздувает уровень до переполнения u8
+    let karma_cap = config.level_curve().karma_for_level(config.params.max_level);
+    let to_karma = amount.min(karma_cap.saturating_sub(account_data.karma));
+    let to_overflow = amount - to_karma;
+    account_data.karma = account_data.karma.checked_add(to_karma).ok_or(VertaError::KarmaOverflow)?;
+    account_data.overflow_karma = account_data
+        .overflow_karma
+        .checked_add(to_overflow)
+        .ok_or(VertaError::KarmaOverflow)?;
+    msg!("Adding {} karma. New karma: {}", to_karma, account_data.karma);
+    if to_overflow > 0 {
+        msg!("Max level reached: {} karma routed to overflow. Overflow karma: {}", to_overflow, account_data.overflow_karma);
+    }
+
+    update_existing_global_stats(program_id, stats_pda, |stats| {
+        stats.total_karma = stats.total_karma.saturating_add(amount);
+    })
+}
+
+// Списывает до `amount` кармы (не ниже нуля); с `include_overflow` сначала списывается
+// overflow_karma. Понижает уровень, если карма его больше не покрывает, и учитывает
+// списание в GlobalStatsAccount::total_karma. Возвращает списанную сумму.
+fn debit_karma(
+    program_id: &Pubkey,
+    config: &ConfigAccount,
+    account_data: &mut UserAccount,
+    stats_pda: &AccountInfo,
+    amount: u64,
+    include_overflow: bool,
+) -> Result<u64, ProgramError> {
+    let from_overflow = if include_overflow {
+        amount.min(account_data.overflow_karma)
+    } else {
+        0
+    };
+    let from_karma = (amount - from_overflow).min(account_data.karma);
+    account_data.overflow_karma -= from_overflow;
+    account_data.karma -= from_karma;
+    lower_level_to_karma(config, account_data);
+
+    let debited = from_overflow + from_karma;
+    update_existing_global_stats(program_id, stats_pda, |stats| {
+        stats.total_karma = stats.total_karma.saturating_sub(debited);
+    })?;
+    Ok(debited)
+}
+
+// Понижает уровень, если карма его больше не покрывает (после списаний)
+fn lower_level_to_karma(config: &ConfigAccount, account_data: &mut UserAccount) {
+    let supported_level = config.level_for_karma(account_data.karma);
+    if account_data.level > supported_level {
+        msg!("Level lowered from {} to {}", account_data.level, supported_level);
+        account_data.level = supported_level;
+    }
+}
+
+// Обработчик инструкции UpdateLevel
+fn process_update_level(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_update_level");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты: PDA пользователя, чей уровень обновляем, конфигурация и статистика
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    // Пороги уровней и награда за уровень берутся из конфигурации
+    let config = load_config(program_id, config_pda)?;
+    let level_curve = config.level_curve();
+
+    // Десериализуем данные аккаунта PDA
+    let mut account_data = load_user_account(program_id, user_pda)?;
+
+    let new_level = config.level_for_karma(account_data.karma);
+    if new_level > account_data.level {
+        // Проверяем, прошёл ли кулдаун с последнего повышения уровня
+        let now = Clock::get()?.unix_timestamp;
+        let elapsed = now.saturating_sub(account_data.last_level_up);
+        let cooldown = config.params.level_up_cooldown;
+        if elapsed < cooldown {
+            msg!("Level-up cooldown active: {} seconds remaining", cooldown - elapsed);
+            return Err(VertaError::CooldownActive.into());
+        }
+
+        // Повышаем только на один уровень, остальная карма ждёт следующего кулдауна
+        account_data.level = account_data.level.checked_add(1).ok_or(VertaError::KarmaOverflow)?;
+        account_data.last_level_up = now;
+        // Награда выдаётся один раз за уровень: после понижения и повторного роста её нет
+        let reward = config.params.level_rewards_enabled() && account_data.level > account_data.rewarded_level;
+        if reward {
+            account_data.rewarded_level = account_data.level;
+        }
+        // Бейдж за уровень тоже выдаётся один раз
+        let badge = config.params.is_badge_level(account_data.level) && !account_data.has_badge(account_data.level);
+        if badge {
+            account_data.set_badge(account_data.level);
+        }
+        msg!("Level updated to {} (karma supports level {})", account_data.level, new_level);
+        LevelUp {
+            user: account_data.owner,
+            level: account_data.level,
+            karma: account_data.karma,
+            timestamp: now,
+        }
+        .emit();
+
+        // Сериализуем обновленные данные обратно
+        store_user_account(user_pda, &mut account_data)?;
+        msg!("User level updated successfully");
+        let level = account_data.level;
+        update_existing_global_stats(program_id, stats_pda, |stats| {
+            stats.highest_level = stats.highest_level.max(level);
+        })?;
+
+        if reward {
+            mint_level_reward(program_id, &config.params, &account_data.owner, accounts_iter)?;
+        }
+        if badge {
+            mint_level_badge(program_id, account_data.level, &account_data.owner, accounts_iter)?;
+        }
+        // Аккаунты NFT-бейджа клиент передаёт, только если пользователь его выпустил
+        if config.params.badge_nft_enabled() && accounts_iter.len() > 0 {
+            update_badge_nft_level(program_id, &config.params, account_data.level, &account_data.owner, accounts_iter)?;
+        }
+    } else {
+        msg!("Level not changed. Current level: {}, required for next: {}", account_data.level, level_curve.karma_for_level(account_data.level.saturating_add(1)));
+    }
+
+    msg!("UpdateLevel instruction processed successfully");
+
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Минтит награду за уровень на ATA владельца аккаунта через CPI в SPL Token.
+// Mint authority токена награды должен быть передан PDA [b"mint_authority"].
+fn mint_level_reward<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    params: &ConfigParams,
+    owner: &Pubkey,
+    accounts_iter: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+) -> ProgramResult {
+    let mint = next_account_info(accounts_iter)?; // Mint токена награды (writable)
+    let user_ata = next_account_info(accounts_iter)?; // ATA владельца для этого mint (writable)
+    let mint_authority = next_account_info(accounts_iter)?; // PDA mint authority
+    let token_program = next_account_info(accounts_iter)?; // Программа SPL Token
+
+    if *mint.key != params.reward_mint {
+        msg!("Reward mint {} does not match the config", mint.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if *token_program.key != spl_token::id() {
+        msg!("Invalid token program provided");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if *user_ata.key != get_associated_token_address(owner, mint.key) {
+        msg!("Token account is not the owner's associated token account");
+        return Err(VertaError::InvalidPda.into());
+    }
+    let (expected_authority, bump) = Pubkey::find_program_address(&[b"mint_authority"], program_id);
+    if expected_authority != *mint_authority.key {
+        msg!("Invalid mint authority PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            token_program.key,
+            mint.key,
+            user_ata.key,
+            mint_authority.key,
+            &[],
+            params.reward_per_level,
+        )?,
+        &[mint.clone(), user_ata.clone(), mint_authority.clone(), token_program.clone()],
+        &[&[b"mint_authority", &[bump]]],
+    )?;
+    msg!("Minted {} reward tokens to {}", params.reward_per_level, user_ata.key);
+    Ok(())
+}
+
+// Выдаёт бейдж за уровень `level`: минтит один непередаваемый токен Token-2022 на ATA владельца.
+// Mint бейджа создаётся заранее инструкцией InitializeBadgeMint, ATA — клиентом.
+fn mint_level_badge<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    level: u8,
+    owner: &Pubkey,
+    accounts_iter: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+) -> ProgramResult {
+    let badge_mint = next_account_info(accounts_iter)?; // PDA mint бейджа (writable)
+    let user_ata = next_account_info(accounts_iter)?; // ATA Token-2022 владельца для бейджа (writable)
+    let mint_authority = next_account_info(accounts_iter)?; // PDA mint authority
+    let token_program = next_account_info(accounts_iter)?; // Программа Token-2022
+
+    let (expected_badge_mint, _) = Pubkey::find_program_address(&[b"badge", &[level]], program_id);
+    if expected_badge_mint != *badge_mint.key {
+        msg!("Invalid badge mint PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if *token_program.key != spl_token_2022::id() {
+        msg!("Invalid token program provided");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if *user_ata.key != get_associated_token_address_with_program_id(owner, badge_mint.key, token_program.key) {
+        msg!("Token account is not the owner's associated token account");
+        return Err(VertaError::InvalidPda.into());
+    }
+    let (expected_authority, bump) = Pubkey::find_program_address(&[b"mint_authority"], program_id);
+    if expected_authority != *mint_authority.key {
+        msg!("Invalid mint authority PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to(
+            token_program.key,
+            badge_mint.key,
+            user_ata.key,
+            mint_authority.key,
+            &[],
+            1,
+        )?,
+        &[badge_mint.clone(), user_ata.clone(), mint_authority.clone(), token_program.clone()],
+        &[&[b"mint_authority", &[bump]]],
+    )?;
+    msg!("Level {} badge minted to {}", level, owner);
+    Ok(())
+}
+
+// Метаданные NFT-бейджа для уровня `level`
+fn badge_nft_data(params: &ConfigParams, level: u8) -> DataV2 {
+    DataV2 {
+        name: format!("Verta Level {}", level),
+        symbol: BADGE_NFT_SYMBOL.to_string(),
+        uri: params.badge_uri(level),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: Some(Collection {
+            verified: false,
+            key: params.badge_collection,
+        }),
+        uses: None,
+    }
+}
+
+// Меняет метаданные NFT-бейджа владельца на уровень `level` через CPI в Token Metadata
+fn update_badge_nft_level<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    params: &ConfigParams,
+    level: u8,
+    owner: &Pubkey,
+    accounts_iter: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+) -> ProgramResult {
+    let badge_nft_mint = next_account_info(accounts_iter)?; // PDA mint NFT-бейджа
+    let metadata = next_account_info(accounts_iter)?; // Метаданные NFT-бейджа (writable)
+    let mint_authority = next_account_info(accounts_iter)?; // PDA mint authority (update authority метаданных)
+    let token_metadata_program = next_account_info(accounts_iter)?; // Программа Token Metadata
+
+    let (expected_nft_mint, _) = Pubkey::find_program_address(&[b"badge_nft", owner.as_ref()], program_id);
+    if expected_nft_mint != *badge_nft_mint.key || *metadata.key != Metadata::find_pda(badge_nft_mint.key).0 {
+        msg!("Invalid badge NFT accounts provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if *token_metadata_program.key != mpl_token_metadata::ID {
+        msg!("Invalid token metadata program provided");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (expected_authority, bump) = Pubkey::find_program_address(&[b"mint_authority"], program_id);
+    if expected_authority != *mint_authority.key {
+        msg!("Invalid mint authority PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    UpdateMetadataAccountV2Cpi::new(
+        token_metadata_program,
+        UpdateMetadataAccountV2CpiAccounts {
+            metadata,
+            update_authority: mint_authority,
+        },
+        UpdateMetadataAccountV2InstructionArgs {
+            data: Some(badge_nft_data(params, level)),
+            new_update_authority: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+        },
+    )
+    .invoke_signed(&[&[b"mint_authority", &[bump]]])?;
+    msg!("Badge NFT metadata of {} updated to level {}", owner, level);
+    Ok(())
+}
+
+// Обработчик инструкции BindExternalIdentity
+fn process_bind_external_identity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    platform_id_hash: [u8; 32],
+) -> ProgramResult {
+    msg!("Entering process_bind_external_identity");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты: пользователь (подписывает) и его PDA
+    let user = next_account_info(accounts_iter)?; // Аккаунт пользователя
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+
+    if !user.is_signer {
+        msg!("User account must be a signer to bind an external identity");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut account_data = load_user_account(program_id, user_pda)?;
+
+    // Аккаунт должен принадлежать подписавшему: чужую привязку изменить нельзя
+    if account_data.owner != *user.key {
+        msg!("Invalid PDA address provided for identity binding");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    account_data.identity_hash = platform_id_hash;
+    store_user_account(user_pda, &mut account_data)?;
+
+    msg!("External identity bound successfully");
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции VerifyContribution
+fn process_verify_contribution(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    user_to_verify: Pubkey,
+    contribution_id: u64,
+    karma_awarded: u64,
+) -> ProgramResult {
+    msg!("Entering process_verify_contribution");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let verifier = next_account_info(accounts_iter)?; // Верификатор (подписывает и платит за запись)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт автора вклада
+    let contribution_pda = next_account_info(accounts_iter)?; // PDA записи о вкладе
+    let verifier_pda = next_account_info(accounts_iter)?; // PDA учёта начислений верификатора
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунта
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+    let verifier_user_pda = next_account_info(accounts_iter).ok(); // PDA пользователя-верификатора (необязательный)
+
+    // Подтверждать вклады может только авторизованный верификатор
+    let config = load_config(program_id, config_pda)?;
+    let weight_bps = check_verifier_authority(program_id, &config, verifier, verifier_pda, verifier_user_pda)?;
+    let karma_awarded = apply_verifier_weight(karma_awarded, weight_bps)?;
+    record_verifier_grant(program_id, &config, verifier, verifier_pda, system_program, karma_awarded)?;
+    if *verifier.key == user_to_verify {
+        msg!("Users cannot verify their own contributions");
+        return Err(VertaError::SelfVerification.into());
+    }
+
+    // Проверка PDA автора вклада
+    let mut user_data = load_user_account(program_id, user_pda)?;
+    if user_data.owner != user_to_verify {
+        msg!("Invalid user PDA address provided for contribution");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    // Проверка PDA записи о вкладе
+    let contribution_id_bytes = contribution_id.to_le_bytes();
+    let (expected_contribution_pda, bump) = Pubkey::find_program_address(
+        &[b"contribution", user_to_verify.as_ref(), &contribution_id_bytes],
+        program_id,
+    );
+    if expected_contribution_pda != *contribution_pda.key {
+        msg!("Invalid contribution PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if !contribution_pda.data_is_empty() {
+        msg!("Contribution {} has already been verified", contribution_id);
+        return Err(VertaError::ContributionAlreadyVerified.into());
+    }
+
+    // Начисляем карму автору вклада
+    // Подтверждённые вклады засчитываются как код
+    credit_karma(program_id, &config, &mut user_data, stats_pda, karma_awarded, KARMA_CATEGORY_CODE, REASON_NONE)?;
+    store_user_account(user_pda, &mut user_data)?;
+
+    // Создаем запись о вкладе за счет верификатора
+    let space = ContributionAccount::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(
+            verifier.key,
+            contribution_pda.key,
+            rent_required,
+            space as u64,
+            program_id,
+        ),
+        &[verifier.clone(), contribution_pda.clone(), system_program.clone()],
+        &[&[b"contribution", user_to_verify.as_ref(), &contribution_id_bytes, &[bump]]],
+    )?;
+
+    let contribution_data = ContributionAccount {
+        contributor: user_to_verify,
+        contribution_id,
+        verifier: *verifier.key,
+        verified_at: Clock::get()?.unix_timestamp,
+        karma_awarded,
+    };
+    contribution_data.write_account_data(&mut contribution_pda.data.borrow_mut())?;
+
+    msg!("Contribution {} verified, {} karma awarded", contribution_id, karma_awarded);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции TopUpAccount
+fn process_top_up_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lamports: u64,
+) -> ProgramResult {
+    msg!("Entering process_top_up_account");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let funder = next_account_info(accounts_iter)?; // Кто пополняет (подписывает и платит)
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для перевода
+
+    if !funder.is_signer {
+        msg!("Funder account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    load_user_account(program_id, user_pda)?;
+
+    invoke(
+        &system_instruction::transfer(funder.key, user_pda.key, lamports),
+        &[funder.clone(), user_pda.clone(), system_program.clone()],
+    )?;
+
+    // Событие о пополнении для индексаторов
+    AccountToppedUp {
+        account: *user_pda.key,
+        payer: *funder.key,
+        lamports,
+    }
+    .emit();
+    msg!("Account {} topped up. New balance: {}", user_pda.key, user_pda.lamports());
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции InitializeConfig
+fn process_initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    admin: Pubkey,
+    params: ConfigParams,
+) -> ProgramResult {
+    msg!("Entering process_initialize_config");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let authority = next_account_info(accounts_iter)?; // Upgrade authority программы (подписывает и платит)
+    let program_data = next_account_info(accounts_iter)?; // ProgramData аккаунт этой программы
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунта
+
+    // Создать конфигурацию может только тот, кто задеплоил программу
+    check_upgrade_authority(program_id, authority, program_data)?;
+
+    let (expected_config_pda, bump) = Pubkey::find_program_address(&[b"config"], program_id);
+    if expected_config_pda != *config_pda.key {
+        msg!("Invalid config PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if !config_pda.data_is_empty() {
+        msg!("Program config is already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if !params.is_valid() {
+        msg!("Invalid config parameters");
+        return Err(VertaError::InvalidConfig.into());
+    }
+
+    let space = ConfigAccount::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            config_pda.key,
+            rent_required,
+            space as u64,
+            program_id,
+        ),
+        &[authority.clone(), config_pda.clone(), system_program.clone()],
+        &[&[b"config", &[bump]]],
+    )?;
+
+    let config = ConfigAccount {
+        version: CONFIG_ACCOUNT_SCHEMA_VERSION,
+        admin,
+        moderation_log_len: 0,
+        pending_authority: None,
+        paused: false,
+        params,
+    };
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("Program config initialized. Admin: {}", admin);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции UpdateConfig
+fn process_update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: ConfigParams,
+) -> ProgramResult {
+    msg!("Entering process_update_config");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+
+    let mut config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+    if !params.is_valid() {
+        msg!("Invalid config parameters");
+        return Err(VertaError::InvalidConfig.into());
+    }
+
+    config.params = params;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("Program config updated: {:?}", config.params);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции ProposeAuthority
+fn process_propose_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_authority: Option<Pubkey>,
+) -> ProgramResult {
+    msg!("Entering process_propose_authority");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Текущий администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+
+    let mut config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+
+    config.pending_authority = new_authority;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    match new_authority {
+        Some(new_authority) => msg!("Admin authority transfer to {} proposed", new_authority),
+        None => msg!("Pending admin authority transfer cancelled"),
+    }
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции AcceptAuthority
+fn process_accept_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_accept_authority");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let new_admin = next_account_info(accounts_iter)?; // Предложенный администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+
+    if !new_admin.is_signer {
+        msg!("New admin account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let mut config = load_config(program_id, config_pda)?;
+    if config.pending_authority != Some(*new_admin.key) {
+        msg!("Signer {} is not the pending admin authority", new_admin.key);
+        return Err(VertaError::Unauthorized.into());
+    }
+
+    let previous_admin = config.admin;
+    config.admin = *new_admin.key;
+    config.pending_authority = None;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("Admin authority transferred from {} to {}", previous_admin, new_admin.key);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции SetReasonCodes
+fn process_set_reason_codes(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    codes: [u16; MAX_REASON_CODES],
+) -> ProgramResult {
+    msg!("Entering process_set_reason_codes");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает и платит)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let reasons_pda = next_account_info(accounts_iter)?; // PDA таблицы причин
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания таблицы
+
+    let config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+
+    let (expected_reasons_pda, bump) = Pubkey::find_program_address(&[b"reasons"], program_id);
+    if expected_reasons_pda != *reasons_pda.key {
+        msg!("Invalid reason table PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if reasons_pda.data_is_empty() {
+        let space = ReasonCodeTableAccount::LEN;
+        let rent_required = Rent::get()?.minimum_balance(space);
+        invoke_signed(
+            &system_instruction::create_account(admin.key, reasons_pda.key, rent_required, space as u64, program_id),
+            &[admin.clone(), reasons_pda.clone(), system_program.clone()],
+            &[&[b"reasons", &[bump]]],
+        )?;
+    } else if reasons_pda.owner != program_id {
+        msg!("Reason table PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let table = ReasonCodeTableAccount { codes };
+    table.write_account_data(&mut reasons_pda.data.borrow_mut())?;
+
+    msg!("Reason table updated: {:?}", codes);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции AddVerifier
+fn process_add_verifier(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    weight: u16,
+) -> ProgramResult {
+    msg!("Entering process_add_verifier");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает и платит)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let verifier = next_account_info(accounts_iter)?; // Кошелёк регистрируемого верификатора
+    let verifier_pda = next_account_info(accounts_iter)?; // PDA верификатора
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания PDA
+
+    let config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+    if weight == 0 || weight > MAX_VERIFIER_WEIGHT_BPS {
+        msg!("Verifier weight must be between 1 and {} bps", MAX_VERIFIER_WEIGHT_BPS);
+        return Err(VertaError::InvalidVerifierWeight.into());
+    }
+
+    let mut record = match load_verifier_record(program_id, verifier.key, verifier_pda)? {
+        Some(record) => record,
+        None => {
+            create_verifier_pda(program_id, admin, verifier.key, verifier_pda, system_program)?;
+            VerifierAccount {
+                verifier: *verifier.key,
+                ..VerifierAccount::default()
+            }
+        }
+    };
+    record.registered = true;
+    record.weight_bps = weight;
+    record.write_account_data(&mut verifier_pda.data.borrow_mut())?;
+
+    msg!("Verifier {} registered with weight {} bps", verifier.key, weight);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции RemoveVerifier
+fn process_remove_verifier(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Entering process_remove_verifier");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let verifier = next_account_info(accounts_iter)?; // Кошелёк верификатора
+    let verifier_pda = next_account_info(accounts_iter)?; // PDA верификатора
+
+    let config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+
+    let Some(mut record) = load_verifier_record(program_id, verifier.key, verifier_pda)? else {
+        msg!("Verifier {} is not registered", verifier.key);
+        return Err(VertaError::AccountNotRegistered.into());
+    };
+    record.registered = false;
+    record.write_account_data(&mut verifier_pda.data.borrow_mut())?;
+
+    msg!("Verifier {} removed", verifier.key);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции ChallengeContribution
+fn process_challenge_contribution(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    contribution_id: u64,
+) -> ProgramResult {
+    msg!("Entering process_challenge_contribution");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let challenger = next_account_info(accounts_iter)?; // Оспаривающий (подписывает, платит ренту и залог)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт автора вклада
+    let contribution_pda = next_account_info(accounts_iter)?; // PDA записи о вкладе
+    let challenge_pda = next_account_info(accounts_iter)?; // PDA записи спора
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания записи
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    if !challenger.is_signer {
+        msg!("Challenger account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let config = load_config(program_id, config_pda)?;
+    let stake = config.params.challenge_stake_lamports;
+    if stake == 0 {
+        msg!("Contribution challenges are disabled in the config");
+        return Err(VertaError::InvalidConfig.into());
+    }
+
+    let mut user_data = load_user_account(program_id, user_pda)?;
+    let contributor = user_data.owner;
+    if contributor == *challenger.key {
+        msg!("Users cannot challenge their own contributions");
+        return Err(VertaError::SelfVerification.into());
+    }
+    let (expected_contribution_pda, _) = Pubkey::find_program_address(
+        &[b"contribution", contributor.as_ref(), &contribution_id.to_le_bytes()],
+        program_id,
+    );
+    if expected_contribution_pda != *contribution_pda.key {
+        msg!("Invalid contribution PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if contribution_pda.data_is_empty() || contribution_pda.owner != program_id {
+        msg!("Contribution {} has not been verified", contribution_id);
+        return Err(VertaError::AccountNotRegistered.into());
+    }
+    let contribution = ContributionAccount::try_from_account_data(&contribution_pda.data.borrow())
+        .map_err(|_| VertaError::InvalidAccountDiscriminator)?;
+
+    let (expected_challenge_pda, bump) =
+        Pubkey::find_program_address(&[b"challenge", contribution_pda.key.as_ref()], program_id);
+    if expected_challenge_pda != *challenge_pda.key {
+        msg!("Invalid challenge PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if !challenge_pda.data_is_empty() {
+        msg!("Contribution {} has already been challenged", contribution_id);
+        return Err(VertaError::AlreadyChallenged.into());
+    }
+
+    // Запись спора хранит залог сверх ренты
+    let space = ChallengeAccount::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    let lamports = rent_required.checked_add(stake).ok_or(ProgramError::ArithmeticOverflow)?;
+    invoke_signed(
+        &system_instruction::create_account(challenger.key, challenge_pda.key, lamports, space as u64, program_id),
+        &[challenger.clone(), challenge_pda.clone(), system_program.clone()],
+        &[&[b"challenge", contribution_pda.key.as_ref(), &[bump]]],
+    )?;
+
+    // Блокируем карму за вклад: как и при списании, сначала снимается карма сверх максимального уровня
+    let karma_locked = debit_karma(program_id, &config, &mut user_data, stats_pda, contribution.karma_awarded, true)?;
+    // Пока спор открыт, автор не может закрыть аккаунт и оставить залог без получателя
+    user_data.open_challenges = user_data.open_challenges.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    store_user_account(user_pda, &mut user_data)?;
+
+    let challenge = ChallengeAccount {
+        contribution: *contribution_pda.key,
+        contributor,
+        challenger: *challenger.key,
+        stake_lamports: stake,
+        karma_locked,
+        opened_at: Clock::get()?.unix_timestamp,
+        resolved: false,
+        upheld: false,
+    };
+    challenge.write_account_data(&mut challenge_pda.data.borrow_mut())?;
+
+    msg!(
+        "Contribution {} of {} challenged by {}: {} karma locked, {} lamports staked",
+        contribution_id,
+        contributor,
+        challenger.key,
+        challenge.karma_locked,
+        stake
+    );
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции ResolveChallenge
+fn process_resolve_challenge(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    upheld: bool,
+) -> ProgramResult {
+    msg!("Entering process_resolve_challenge");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let challenge_pda = next_account_info(accounts_iter)?; // PDA записи спора
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт автора вклада
+    let challenger = next_account_info(accounts_iter)?; // Кошелёк оспаривающего (получает залог обратно)
+    let treasury_pda = next_account_info(accounts_iter)?; // PDA казны (получает залог при отклонении)
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания казны
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    let config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+
+    if challenge_pda.data_is_empty() || challenge_pda.owner != program_id {
+        msg!("Challenge {} does not exist", challenge_pda.key);
+        return Err(VertaError::AccountNotRegistered.into());
+    }
+    let mut challenge = ChallengeAccount::try_from_account_data(&challenge_pda.data.borrow())
+        .map_err(|_| VertaError::InvalidAccountDiscriminator)?;
+    if challenge.resolved {
+        msg!("Challenge {} has already been resolved", challenge_pda.key);
+        return Err(VertaError::ChallengeResolved.into());
+    }
+    if challenge.challenger != *challenger.key {
+        msg!("Account {} is not the challenger", challenger.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut user_data = load_user_account(program_id, user_pda)?;
+    if user_data.owner != challenge.contributor {
+        msg!("Invalid user PDA address provided for challenge");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    // Удовлетворённый спор: карма остаётся снятой, залог возвращается.
+    // Отклонённый: карма возвращается автору, залог уходит в казну.
+    // Споры, открытые до появления счётчика, в нём не учтены, поэтому вычитаем с насыщением
+    user_data.open_challenges = user_data.open_challenges.saturating_sub(1);
+    let stake_destination = if upheld {
+        store_user_account(user_pda, &mut user_data)?;
+        challenger
+    } else {
+        add_capped_karma(program_id, &config, &mut user_data, stats_pda, challenge.karma_locked)?;
+        store_user_account(user_pda, &mut user_data)?;
+        load_or_create_treasury(program_id, admin, treasury_pda, system_program)?;
+        treasury_pda
+    };
+    **challenge_pda.lamports.borrow_mut() = challenge_pda
+        .lamports()
+        .checked_sub(challenge.stake_lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **stake_destination.lamports.borrow_mut() = stake_destination
+        .lamports()
+        .checked_add(challenge.stake_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if !upheld {
+        update_global_stats(program_id, admin, stats_pda, system_program, |stats| {
+            stats.treasury_lamports = treasury_pda.lamports();
+        })?;
+    }
+
+    challenge.resolved = true;
+    challenge.upheld = upheld;
+    challenge.write_account_data(&mut challenge_pda.data.borrow_mut())?;
+
+    msg!(
+        "Challenge {} {}: {} karma {}",
+        challenge_pda.key,
+        if upheld { "upheld" } else { "rejected" },
+        challenge.karma_locked,
+        if upheld { "clawed back" } else { "restored" }
+    );
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции MigrateConfig
+fn process_migrate_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_migrate_config");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let payer = next_account_info(accounts_iter)?; // Кто вносит недостающую ренту (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для перевода
+
+    if !payer.is_signer {
+        msg!("Payer account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (expected_config_pda, _) = Pubkey::find_program_address(&[b"config"], program_id);
+    if expected_config_pda != *config_pda.key {
+        msg!("Invalid config PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if config_pda.owner != program_id {
+        msg!("Config PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let Some(config) = upgrade_config_data(&config_pda.data.borrow())? else {
+        msg!("Program config is already at schema version {}", CONFIG_ACCOUNT_SCHEMA_VERSION);
+        return Ok(());
+    };
+
+    let rent_required = Rent::get()?.minimum_balance(ConfigAccount::LEN);
+    let shortfall = rent_required.saturating_sub(config_pda.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, config_pda.key, shortfall),
+            &[payer.clone(), config_pda.clone(), system_program.clone()],
+        )?;
+    }
+
+    config_pda.realloc(ConfigAccount::LEN, false)?;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!(
+        "Program config migrated to schema version {} ({} lamports paid by {})",
+        CONFIG_ACCOUNT_SCHEMA_VERSION,
+        shortfall,
+        payer.key
+    );
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции SetPaused
+fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+) -> ProgramResult {
+    msg!("Entering process_set_paused");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+
+    let mut config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+
+    config.paused = paused;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("Program {} by {}", if paused { "paused" } else { "unpaused" }, admin.key);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции InitializeBadgeMint
+fn process_initialize_badge_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    level: u8,
+) -> ProgramResult {
+    msg!("Entering process_initialize_badge_mint");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает и платит)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let badge_mint = next_account_info(accounts_iter)?; // PDA mint бейджа (writable)
+    let mint_authority = next_account_info(accounts_iter)?; // PDA mint authority
+    let token_program = next_account_info(accounts_iter)?; // Программа Token-2022
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунта
+
+    let config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+    if level == 0 || level > config.params.max_level {
+        msg!("Badge level must be between 1 and {}", config.params.max_level);
+        return Err(VertaError::InvalidConfig.into());
+    }
+    if *token_program.key != spl_token_2022::id() {
+        msg!("Invalid token program provided");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (expected_badge_mint, bump) = Pubkey::find_program_address(&[b"badge", &[level]], program_id);
+    if expected_badge_mint != *badge_mint.key {
+        msg!("Invalid badge mint PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    let (expected_authority, _) = Pubkey::find_program_address(&[b"mint_authority"], program_id);
+    if expected_authority != *mint_authority.key {
+        msg!("Invalid mint authority PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if !badge_mint.data_is_empty() {
+        msg!("Badge mint for level {} is already initialized", level);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // Mint с расширением NonTransferable: токены нельзя передать, только сжечь
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::NonTransferable])?;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(admin.key, badge_mint.key, rent_required, space as u64, token_program.key),
+        &[admin.clone(), badge_mint.clone(), system_program.clone()],
+        &[&[b"badge", &[level], &[bump]]],
+    )?;
+    // Расширение инициализируется до самого mint
+    invoke(
+        &spl_token_2022::instruction::initialize_non_transferable_mint(token_program.key, badge_mint.key)?,
+        &[badge_mint.clone(), token_program.clone()],
+    )?;
+    invoke(
+        &spl_token_2022::instruction::initialize_mint2(token_program.key, badge_mint.key, mint_authority.key, None, 0)?,
+        &[badge_mint.clone(), token_program.clone()],
+    )?;
+
+    msg!("Badge mint for level {} initialized at {}", level, badge_mint.key);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции MintBadgeNft
+fn process_mint_badge_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    msg!("Entering process_mint_badge_nft");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let user = next_account_info(accounts_iter)?; // Владелец аккаунта (подписывает и платит)
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let badge_nft_mint = next_account_info(accounts_iter)?; // PDA mint NFT-бейджа (writable)
+    let user_ata = next_account_info(accounts_iter)?; // ATA пользователя для NFT (writable)
+    let metadata = next_account_info(accounts_iter)?; // Метаданные NFT (writable)
+    let master_edition = next_account_info(accounts_iter)?; // Master edition NFT (writable)
+    let mint_authority = next_account_info(accounts_iter)?; // PDA mint authority
+    let token_program = next_account_info(accounts_iter)?; // Программа SPL Token
+    let associated_token_program = next_account_info(accounts_iter)?; // Программа Associated Token Account
+    let token_metadata_program = next_account_info(accounts_iter)?; // Программа Token Metadata
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунтов
+    let payer = optional_payer(accounts_iter, user)?; // Плательщик ренты
+
+    if !user.is_signer {
+        msg!("User account must be a signer to mint a badge NFT");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let config = load_config(program_id, config_pda)?;
+    if !config.params.badge_nft_enabled() {
+        msg!("Badge NFTs are not enabled in the config");
+        return Err(VertaError::InvalidConfig.into());
+    }
+    let account_data = load_user_account(program_id, user_pda)?;
+    if account_data.owner != *user.key {
+        msg!("Invalid PDA address provided for badge NFT");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    let (expected_nft_mint, nft_bump) = Pubkey::find_program_address(&[b"badge_nft", user.key.as_ref()], program_id);
+    if expected_nft_mint != *badge_nft_mint.key {
+        msg!("Invalid badge NFT mint PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if !badge_nft_mint.data_is_empty() {
+        msg!("Badge NFT is already minted for {}", user.key);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    let (expected_authority, authority_bump) = Pubkey::find_program_address(&[b"mint_authority"], program_id);
+    if expected_authority != *mint_authority.key {
+        msg!("Invalid mint authority PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if *token_program.key != spl_token::id() || *token_metadata_program.key != mpl_token_metadata::ID {
+        msg!("Invalid token program provided");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if *metadata.key != Metadata::find_pda(badge_nft_mint.key).0
+        || *master_edition.key != MasterEdition::find_pda(badge_nft_mint.key).0
+    {
+        msg!("Invalid metadata or master edition address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    let authority_seeds: &[&[u8]] = &[b"mint_authority", &[authority_bump]];
+
+    // Mint NFT: без десятичных знаков, mint authority — PDA программы
+    let space = spl_token::state::Mint::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(payer.key, badge_nft_mint.key, rent_required, space as u64, token_program.key),
+        &[payer.clone(), badge_nft_mint.clone(), system_program.clone()],
+        &[&[b"badge_nft", user.key.as_ref(), &[nft_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint2(token_program.key, badge_nft_mint.key, mint_authority.key, None, 0)?,
+        &[badge_nft_mint.clone(), token_program.clone()],
+    )?;
+
+    // ATA пользователя и единственный токен NFT
+    invoke(
+        &create_associated_token_account_idempotent(payer.key, user.key, badge_nft_mint.key, token_program.key),
+        &[
+            payer.clone(),
+            user_ata.clone(),
+            user.clone(),
+            badge_nft_mint.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::mint_to(token_program.key, badge_nft_mint.key, user_ata.key, mint_authority.key, &[], 1)?,
+        &[badge_nft_mint.clone(), user_ata.clone(), mint_authority.clone(), token_program.clone()],
+        &[authority_seeds],
+    )?;
+
+    // Метаданные с текущим уровнем; изменяемые, чтобы UpdateLevel мог обновлять URI
+    CreateMetadataAccountV3Cpi::new(
+        token_metadata_program,
+        CreateMetadataAccountV3CpiAccounts {
+            metadata,
+            mint: badge_nft_mint,
+            mint_authority,
+            payer,
+            update_authority: (mint_authority, true),
+            system_program,
+            rent: None,
+        },
+        CreateMetadataAccountV3InstructionArgs {
+            data: badge_nft_data(&config.params, account_data.level),
+            is_mutable: true,
+            collection_details: None,
+        },
+    )
+    .invoke_signed(&[authority_seeds])?;
+
+    // Master edition с нулевым тиражом: mint authority переходит к edition, новых токенов не будет
+    CreateMasterEditionV3Cpi::new(
+        token_metadata_program,
+        CreateMasterEditionV3CpiAccounts {
+            edition: master_edition,
+            mint: badge_nft_mint,
+            update_authority: mint_authority,
+            mint_authority,
+            payer,
+            metadata,
+            token_program,
+            system_program,
+            rent: None,
+        },
+        CreateMasterEditionV3InstructionArgs { max_supply: Some(0) },
+    )
+    .invoke_signed(&[authority_seeds])?;
+
+    msg!("Badge NFT {} minted to {} at level {}", badge_nft_mint.key, user.key, account_data.level);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Находится ли программа на паузе: ищет конфигурацию среди аккаунтов инструкции.
+// Без переданной (или созданной) конфигурации пауза не действует.
+fn is_paused(program_id: &Pubkey, accounts: &[AccountInfo]) -> Result<bool, ProgramError> {
+    let (config_address, _) = Pubkey::find_program_address(&[b"config"], program_id);
+    match accounts.iter().find(|account| *account.key == config_address) {
+        Some(config_pda) if !config_pda.data_is_empty() => Ok(load_config(program_id, config_pda)?.paused),
+        _ => Ok(false),
+    }
+}
+
+// Обработчик инструкции GetProgramInfo
+fn process_get_program_info() -> ProgramResult {
+    let info = ProgramInfo::current();
+    set_return_data(&info.try_to_vec()?);
+
+    msg!("Program info: {:?}", info);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции SlashKarma
+fn process_slash_karma(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    reason_code: u16,
+    reason_hash: [u8; 32],
+) -> ProgramResult {
+    msg!("Entering process_slash_karma");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let moderator = next_account_info(accounts_iter)?; // Администратор из конфигурации (подписывает и платит за запись журнала)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let log_pda = next_account_info(accounts_iter)?; // PDA новой записи журнала модерации
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания записи
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    // Списывать карму может только администратор
+    let mut config = load_config(program_id, config_pda)?;
+    check_admin(&config, moderator)?;
+
+    let mut account_data = load_user_account(program_id, user_pda)?;
+
+    // Сначала списываем карму сверх максимального уровня, затем основную;
+    // уровень понижается, если карма его больше не покрывает
+    create_global_stats(program_id, moderator, stats_pda, system_program)?;
+    let slashed = debit_karma(program_id, &config, &mut account_data, stats_pda, amount, true)?;
+    msg!(
+        "Slashed {} karma (reason code {}). New karma: {}, overflow karma: {}",
+        slashed,
+        reason_code,
+        account_data.karma,
+        account_data.overflow_karma
+    );
+
+    store_user_account(user_pda, &mut account_data)?;
+
+    let entry = ModerationLogAccount {
+        index: config.moderation_log_len,
+        actor: *moderator.key,
+        target: account_data.owner,
+        action: MODERATION_ACTION_SLASH,
+        reason_code,
+        reason_hash,
+        amount: slashed,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    append_moderation_log(program_id, moderator, log_pda, system_program, &entry)?;
+    config.moderation_log_len = config.moderation_log_len.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("SlashKarma instruction processed successfully");
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкций FreezeUser и UnfreezeUser
+fn process_set_user_frozen(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    frozen: bool,
+    reason_code: u16,
+    reason_hash: [u8; 32],
+) -> ProgramResult {
+    msg!("Entering process_set_user_frozen");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let moderator = next_account_info(accounts_iter)?; // Администратор из конфигурации (подписывает и платит за запись журнала)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let log_pda = next_account_info(accounts_iter)?; // PDA новой записи журнала модерации
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания записи
+
+    // Замораживать аккаунты может только администратор
+    let mut config = load_config(program_id, config_pda)?;
+    check_admin(&config, moderator)?;
+
+    let mut account_data = load_user_account(program_id, user_pda)?;
+    if account_data.frozen == frozen {
+        msg!("User account {} is already {}", user_pda.key, if frozen { "frozen" } else { "unfrozen" });
+        return Ok(());
+    }
+    account_data.frozen = frozen;
+    store_user_account(user_pda, &mut account_data)?;
+
+    let entry = ModerationLogAccount {
+        index: config.moderation_log_len,
+        actor: *moderator.key,
+        target: account_data.owner,
+        action: if frozen { MODERATION_ACTION_FREEZE } else { MODERATION_ACTION_UNFREEZE },
+        reason_code,
+        reason_hash,
+        amount: 0,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    append_moderation_log(program_id, moderator, log_pda, system_program, &entry)?;
+    config.moderation_log_len = config.moderation_log_len.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("User account {} {}", user_pda.key, if frozen { "frozen" } else { "unfrozen" });
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции IssueStrike
+fn process_issue_strike(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reason_code: u16,
+    reason_hash: [u8; 32],
+) -> ProgramResult {
+    msg!("Entering process_issue_strike");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let moderator = next_account_info(accounts_iter)?; // Администратор из конфигурации (подписывает и платит за запись журнала)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let log_pda = next_account_info(accounts_iter)?; // PDA новой записи журнала модерации
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания записи
+
+    // Выносить предупреждения может только администратор
+    let mut config = load_config(program_id, config_pda)?;
+    check_admin(&config, moderator)?;
+
+    let mut account_data = load_user_account(program_id, user_pda)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Предупреждения, пережившие испытательный срок, сгорают до учёта нового
+    let strikes = account_data
+        .active_strikes(now, config.params.strike_probation_secs)
+        .saturating_add(1);
+    account_data.strikes = strikes;
+    account_data.last_strike_at = now;
+
+    let threshold = config.params.strike_freeze_threshold;
+    let auto_freeze = threshold > 0 && strikes >= threshold && !account_data.frozen;
+    if auto_freeze {
+        account_data.frozen = true;
+    }
+    store_user_account(user_pda, &mut account_data)?;
+
+    let entry = ModerationLogAccount {
+        index: config.moderation_log_len,
+        actor: *moderator.key,
+        target: account_data.owner,
+        action: if auto_freeze { MODERATION_ACTION_STRIKE_FREEZE } else { MODERATION_ACTION_STRIKE },
+        reason_code,
+        reason_hash,
+        amount: strikes as u64,
+        timestamp: now,
+    };
+    append_moderation_log(program_id, moderator, log_pda, system_program, &entry)?;
+    config.moderation_log_len = config.moderation_log_len.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    config.write_account_data(&mut config_pda.data.borrow_mut())?;
+
+    msg!("User account {} has {} active strike(s) (reason code {})", user_pda.key, strikes, reason_code);
+    if auto_freeze {
+        msg!("Strike threshold {} reached: user account {} frozen", threshold, user_pda.key);
+    }
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Создаёт PDA записи журнала модерации с индексом entry.index за счёт `payer`
+fn append_moderation_log<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    log_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    entry: &ModerationLogAccount,
+) -> ProgramResult {
+    let index_bytes = entry.index.to_le_bytes();
+    let (expected_log_pda, bump) = Pubkey::find_program_address(&[b"modlog", &index_bytes], program_id);
+    if expected_log_pda != *log_pda.key {
+        msg!("Invalid moderation log PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if !log_pda.data_is_empty() {
+        msg!("Moderation log entry {} already exists", entry.index);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let space = ModerationLogAccount::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(payer.key, log_pda.key, rent_required, space as u64, program_id),
+        &[payer.clone(), log_pda.clone(), system_program.clone()],
+        &[&[b"modlog", &index_bytes, &[bump]]],
+    )?;
+    entry.write_account_data(&mut log_pda.data.borrow_mut())?;
+
+    msg!("Moderation log entry {}: action {} on {} by {}", entry.index, entry.action, entry.target, entry.actor);
+
+    Ok(())
+}
+
+// Обработчик инструкции TransferKarma
+fn process_transfer_karma(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    msg!("Entering process_transfer_karma");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let sender = next_account_info(accounts_iter)?; // Отправитель (подписывает, платит за создание казны)
+    let sender_pda = next_account_info(accounts_iter)?; // PDA аккаунт отправителя
+    let recipient_pda = next_account_info(accounts_iter)?; // PDA аккаунт получателя
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let treasury_pda = next_account_info(accounts_iter)?; // PDA казны
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания казны
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    if !sender.is_signer {
+        msg!("Sender account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let config = load_config(program_id, config_pda)?;
+
+    if sender_pda.key == recipient_pda.key {
+        msg!("Cannot transfer karma to yourself");
+        return Err(VertaError::SelfTransfer.into());
+    }
+
+    let mut sender_data = load_user_account(program_id, sender_pda)?;
+    let mut recipient_data = load_user_account(program_id, recipient_pda)?;
+    if sender_data.owner != *sender.key {
+        msg!("Invalid sender PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if sender_data.frozen || recipient_data.frozen {
+        msg!("Karma cannot be transferred to or from a frozen account");
+        return Err(VertaError::AccountFrozen.into());
+    }
+
+    if sender_data.karma < amount {
+        msg!("Insufficient karma: {} available, {} requested", sender_data.karma, amount);
+        return Err(VertaError::InsufficientKarma.into());
+    }
+
+    let fee = config.params.transfer_fee(amount);
+    let received = amount - fee;
+
+    // Комиссия уходит из кармы пользователей: total_karma уменьшается на неё
+    debit_karma(program_id, &config, &mut sender_data, stats_pda, amount, false)?;
+    // Переводы между пользователями засчитываются как вклад в сообщество
+    add_category_karma(&mut recipient_data, KARMA_CATEGORY_COMMUNITY, received)?;
+    add_capped_karma(program_id, &config, &mut recipient_data, stats_pda, received)?;
+
+    if fee > 0 {
+        match config.params.transfer_fee_destination {
+            TRANSFER_FEE_TREASURY => credit_treasury(program_id, sender, treasury_pda, system_program, fee)?,
+            _ => msg!("Burned {} karma transfer fee", fee),
+        }
+    }
+
+    store_user_account(sender_pda, &mut sender_data)?;
+    store_user_account(recipient_pda, &mut recipient_data)?;
+
+    msg!("Transferred {} karma from {} to {} (fee {})", received, sender_pda.key, recipient_pda.key, fee);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Зачисляет карму в казну, создавая PDA казны при первом зачислении за счёт `payer`
+fn credit_treasury<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    treasury_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    karma: u64,
+) -> ProgramResult {
+    let mut treasury = load_or_create_treasury(program_id, payer, treasury_pda, system_program)?;
+    treasury.karma = treasury.karma.checked_add(karma).ok_or(VertaError::KarmaOverflow)?;
+    treasury.write_account_data(&mut treasury_pda.data.borrow_mut())?;
+
+    msg!("Treasury credited with {} karma. Treasury karma: {}", karma, treasury.karma);
+
+    Ok(())
+}
+
+// Загружает казну, создавая её PDA за счёт `payer`, если она ещё не существует
+fn load_or_create_treasury<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    treasury_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<TreasuryAccount, ProgramError> {
+    let (expected_treasury_pda, bump) = Pubkey::find_program_address(&[b"treasury"], program_id);
+    if expected_treasury_pda != *treasury_pda.key {
+        msg!("Invalid treasury PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    if treasury_pda.data_is_empty() {
+        let space = TreasuryAccount::LEN;
+        let rent_required = Rent::get()?.minimum_balance(space);
+        invoke_signed(
+            &system_instruction::create_account(payer.key, treasury_pda.key, rent_required, space as u64, program_id),
+            &[payer.clone(), treasury_pda.clone(), system_program.clone()],
+            &[&[b"treasury", &[bump]]],
+        )?;
+    } else if treasury_pda.owner != program_id {
+        msg!("Treasury PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    } else if treasury_pda.data_len() < TreasuryAccount::LEN {
+        msg!("Treasury account data is too small: {} bytes", treasury_pda.data_len());
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    let treasury = if treasury_pda.data.borrow().iter().all(|&byte| byte == 0) {
+        // Только что созданная казна: записываем дискриминатор сразу
+        let treasury = TreasuryAccount::default();
+        treasury.write_account_data(&mut treasury_pda.data.borrow_mut())?;
+        treasury
+    } else if TreasuryAccount::has_discriminator(&treasury_pda.data.borrow()) {
+        TreasuryAccount::try_from_account_data(&treasury_pda.data.borrow())?
+    } else {
+        msg!("Account {} is not a treasury account", treasury_pda.key);
+        return Err(VertaError::InvalidAccountDiscriminator.into());
+    };
+    Ok(treasury)
+}
+
+// Плата за регистрацию из конфигурации; пока конфигурация не создана, регистрация бесплатна
+fn registration_fee(program_id: &Pubkey, config_pda: &AccountInfo) -> Result<u64, ProgramError> {
+    let (expected_config_pda, _) = Pubkey::find_program_address(&[b"config"], program_id);
+    if expected_config_pda != *config_pda.key {
+        msg!("Invalid config PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if config_pda.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(load_config(program_id, config_pda)?.params.registration_fee_lamports)
+}
+
+// Изменяет глобальную статистику, создавая её PDA за счёт `payer`, если она ещё не существует
+fn update_global_stats<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    stats_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    update: impl FnOnce(&mut GlobalStatsAccount),
+) -> ProgramResult {
+    create_global_stats(program_id, payer, stats_pda, system_program)?;
+    update_existing_global_stats(program_id, stats_pda, update)
+}
+
+// Создаёт PDA глобальной статистики за счёт `payer`, если она ещё не существует
+fn create_global_stats<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    stats_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (expected_stats_pda, bump) = Pubkey::find_program_address(&[b"stats"], program_id);
+    if expected_stats_pda != *stats_pda.key {
+        msg!("Invalid global stats PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if stats_pda.data_is_empty() {
+        let space = GlobalStatsAccount::LEN;
+        let rent_required = Rent::get()?.minimum_balance(space);
+        invoke_signed(
+            &system_instruction::create_account(payer.key, stats_pda.key, rent_required, space as u64, program_id),
+            &[payer.clone(), stats_pda.clone(), system_program.clone()],
+            &[&[b"stats", &[bump]]],
+        )?;
+        GlobalStatsAccount::default().write_account_data(&mut stats_pda.data.borrow_mut())?;
+    }
+    Ok(())
+}
+
+// Изменяет глобальную статистику, если её PDA уже создан. Инструкции без плательщика
+// (UpdateLevel, DecayKarma) статистику не создают: до её создания изменения не учитываются.
+fn update_existing_global_stats(
+    program_id: &Pubkey,
+    stats_pda: &AccountInfo,
+    update: impl FnOnce(&mut GlobalStatsAccount),
+) -> ProgramResult {
+    let (expected_stats_pda, _) = Pubkey::find_program_address(&[b"stats"], program_id);
+    if expected_stats_pda != *stats_pda.key {
+        msg!("Invalid global stats PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if stats_pda.data_is_empty() {
+        return Ok(());
+    }
+    if stats_pda.owner != program_id {
+        msg!("Global stats PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut stats = GlobalStatsAccount::try_from_account_data(&stats_pda.data.borrow())
+        .map_err(|_| VertaError::InvalidAccountDiscriminator)?;
+    update(&mut stats);
+    stats.write_account_data(&mut stats_pda.data.borrow_mut())?;
+    Ok(())
+}
+
+// Учитывает регистрацию в индексе шарда `shard`, создавая индекс при первой регистрации
+fn update_shard_index<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    shard_index_pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    shard: u8,
+    registered_at: i64,
+) -> ProgramResult {
+    let (expected_shard_index_pda, bump) = Pubkey::find_program_address(&[b"shard", &[shard]], program_id);
+    if expected_shard_index_pda != *shard_index_pda.key {
+        msg!("Invalid shard index PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    let mut index = if shard_index_pda.data_is_empty() {
+        let space = ShardIndexAccount::LEN;
+        let rent_required = Rent::get()?.minimum_balance(space);
+        invoke_signed(
+            &system_instruction::create_account(payer.key, shard_index_pda.key, rent_required, space as u64, program_id),
+            &[payer.clone(), shard_index_pda.clone(), system_program.clone()],
+            &[&[b"shard", &[shard], &[bump]]],
+        )?;
+        ShardIndexAccount {
+            shard,
+            ..ShardIndexAccount::default()
+        }
+    } else if shard_index_pda.owner != program_id {
+        msg!("Shard index PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    } else {
+        ShardIndexAccount::try_from_account_data(&shard_index_pda.data.borrow())
+            .map_err(|_| VertaError::InvalidAccountDiscriminator)?
+    };
+    index.user_count = index.user_count.saturating_add(1);
+    index.last_registered_at = registered_at;
+    index.write_account_data(&mut shard_index_pda.data.borrow_mut())?;
+    Ok(())
+}
+
+// Обработчик инструкции WithdrawTreasury
+fn process_withdraw_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lamports: u64,
+) -> ProgramResult {
+    msg!("Entering process_withdraw_treasury");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let admin = next_account_info(accounts_iter)?; // Администратор (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let treasury_pda = next_account_info(accounts_iter)?; // PDA казны
+    let destination = next_account_info(accounts_iter)?; // Получатель лампортов
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания статистики
+
+    let config = load_config(program_id, config_pda)?;
+    check_admin(&config, admin)?;
+
+    let (expected_treasury_pda, _) = Pubkey::find_program_address(&[b"treasury"], program_id);
+    if expected_treasury_pda != *treasury_pda.key {
+        msg!("Invalid treasury PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    if treasury_pda.owner != program_id || !TreasuryAccount::has_discriminator(&treasury_pda.data.borrow()) {
+        msg!("Treasury has not been created yet");
+        return Err(VertaError::InsufficientTreasuryFunds.into());
+    }
+
+    // Минимум для освобождения от ренты остаётся в казне
+    let reserve = Rent::get()?.minimum_balance(treasury_pda.data_len());
+    let available = treasury_pda.lamports().saturating_sub(reserve);
+    if lamports > available {
+        msg!("Requested {} lamports, only {} available in the treasury", lamports, available);
+        return Err(VertaError::InsufficientTreasuryFunds.into());
+    }
+
+    **treasury_pda.lamports.borrow_mut() -= lamports;
+    **destination.lamports.borrow_mut() = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    update_global_stats(program_id, admin, stats_pda, system_program, |stats| {
+        stats.treasury_withdrawn = stats.treasury_withdrawn.saturating_add(lamports);
+        stats.treasury_lamports = treasury_pda.lamports();
+    })?;
+
+    msg!("Withdrew {} lamports from the treasury to {}", lamports, destination.key);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции CloseUserAccount
+fn process_close_user_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_close_user_account");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let user = next_account_info(accounts_iter)?; // Владелец аккаунта (подписывает)
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+    let sponsor = next_account_info(accounts_iter).ok(); // Спонсор, вносивший ренту (если не владелец)
+
+    if !user.is_signer {
+        msg!("User account must be a signer to close the account");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let account_data = load_user_account(program_id, user_pda)?;
+    if account_data.owner != *user.key {
+        msg!("Signer {} does not own user account {}", user.key, user_pda.key);
+        return Err(VertaError::Unauthorized.into());
+    }
+    // Иначе заморозку можно было бы обойти, закрыв аккаунт и зарегистрировавшись заново
+    if account_data.frozen {
+        msg!("A frozen account cannot be closed");
+        return Err(VertaError::AccountFrozen.into());
+    }
+    // Залог открытого спора возвращается или уходит в казну при разрешении, которому нужен аккаунт автора
+    if account_data.open_challenges > 0 {
+        msg!("User account has {} unresolved challenges", account_data.open_challenges);
+        return Err(VertaError::ChallengeOpen.into());
+    }
+    // Отметки о наградах и бейджах хранятся в аккаунте: после закрытия и повторной регистрации
+    // их можно было бы получить снова
+    if account_data.rewarded_level > 0 || account_data.badges_awarded != [0; 32] {
+        msg!("Rewards up to level {} were already claimed; the account cannot be closed", account_data.rewarded_level);
+        return Err(VertaError::RewardsClaimed.into());
+    }
+
+    // Рента возвращается тому, кто её вносил
+    let rent_recipient = if account_data.rent_payer == *user.key {
+        user
+    } else {
+        match sponsor {
+            Some(sponsor) if *sponsor.key == account_data.rent_payer => sponsor,
+            _ => {
+                msg!("Rent was paid by {}; pass it as the last account", account_data.rent_payer);
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        }
+    };
+
+    // Карма закрытого аккаунта больше не входит в общую сумму, а сам он — в число пользователей
+    let karma = account_data.karma.saturating_add(account_data.overflow_karma);
+    update_existing_global_stats(program_id, stats_pda, |stats| {
+        stats.total_karma = stats.total_karma.saturating_sub(karma);
+        stats.total_users = stats.total_users.saturating_sub(1);
+    })?;
+
+    // Возвращаем все лампорты плательщику ренты
+    let lamports = user_pda.lamports();
+    **rent_recipient.lamports.borrow_mut() =
+        rent_recipient.lamports().checked_add(lamports).ok_or(ProgramError::ArithmeticOverflow)?;
+    **user_pda.lamports.borrow_mut() = 0;
+
+    // Обнуляем данные и отдаём аккаунт системной программе
+    user_pda.data.borrow_mut().fill(0);
+    user_pda.realloc(0, false)?;
+    user_pda.assign(&solana_program::system_program::id());
+
+    msg!("User account {} closed, {} lamports returned to {}", user_pda.key, lamports, rent_recipient.key);
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции DecayKarma
+fn process_decay_karma(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_decay_karma");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты: PDA пользователя, конфигурация и статистика. Подпись не нужна.
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    let config = load_config(program_id, config_pda)?;
+    let mut account_data = load_user_account(program_id, user_pda)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let elapsed = now.saturating_sub(account_data.last_decay_at).max(0) as u64;
+    let decayed = decay(account_data.karma, elapsed, config.params.karma_half_life);
+
+    // Без нулевой кармы и без изменений аккаунт не сохраняем: иначе частые вызовы сдвигали
+    // бы last_decay_at и округление вниз не давало бы карме затухать. При нулевой карме
+    // отсчёт сдвигаем, чтобы будущие начисления не затухали за время, когда их не было.
+    if decayed == account_data.karma && account_data.karma > 0 {
+        msg!("No karma to decay");
+        return Ok(());
+    }
+
+    msg!("Karma decayed from {} to {} after {} seconds", account_data.karma, decayed, elapsed);
+    let lost = account_data.karma - decayed;
+    debit_karma(program_id, &config, &mut account_data, stats_pda, lost, false)?;
+    account_data.last_decay_at = now;
+    store_user_account(user_pda, &mut account_data)?;
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции AddKarmaWithApprovals
+fn process_add_karma_with_approvals(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    category: u8,
+    envelope: GrantEnvelope,
+) -> ProgramResult {
+    msg!("Entering process_add_karma_with_approvals");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let submitter = next_account_info(accounts_iter)?; // Кто отправляет транзакцию (подписывает)
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт получателя
+    let instructions_sysvar = next_account_info(accounts_iter)?; // Sysvar Instructions
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+
+    if !submitter.is_signer {
+        msg!("Submitter account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let config = load_config(program_id, config_pda)?;
+    let threshold = config.params.grant_threshold as usize;
+    if threshold == 0 {
+        msg!("Multi-signer grants are disabled");
+        return Err(VertaError::Unauthorized.into());
+    }
+
+    let mut account_data = load_user_account(program_id, user_pda)?;
+
+    if Clock::get()?.unix_timestamp > envelope.expires_at {
+        msg!("Grant approvals expired at {}", envelope.expires_at);
+        return Err(VertaError::GrantExpired.into());
+    }
+
+    let message = GrantMessage {
+        program_id: *program_id,
+        user: account_data.owner,
+        amount,
+        category,
+        nonce: account_data.grant_nonce,
+        expires_at: envelope.expires_at,
+    }
+    .try_to_vec()?;
+
+    // Каждое одобрение должно быть подписью ключа из конфигурации над этим сообщением,
+    // проверенной программой Ed25519 в этой же транзакции
+    let verified = verified_ed25519_signatures(instructions_sysvar)?;
+    let mut approvers: Vec<Pubkey> = Vec::with_capacity(envelope.approvals.len());
+    for approval in &envelope.approvals {
+        if !config.params.is_grant_cosigner(&approval.signer) {
+            msg!("Key {} is not a grant co-signer", approval.signer);
+            return Err(VertaError::InvalidApproval.into());
+        }
+        let is_verified = verified.iter().any(|(signer, signature, signed_message)| {
+            *signer == approval.signer && *signature == approval.signature && *signed_message == message
+        });
+        if !is_verified {
+            msg!("Approval of {} is not verified by the Ed25519 program", approval.signer);
+            return Err(VertaError::InvalidApproval.into());
+        }
+        if !approvers.contains(&approval.signer) {
+            approvers.push(approval.signer);
+        }
+    }
+    if approvers.len() < threshold {
+        msg!("Not enough approvals: {} of {}", approvers.len(), threshold);
+        return Err(VertaError::InsufficientApprovals.into());
+    }
+
+    credit_karma(program_id, &config, &mut account_data, stats_pda, amount, category, REASON_NONE)?;
+    account_data.grant_nonce = account_data.grant_nonce.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    store_user_account(user_pda, &mut account_data)?;
+
+    msg!("Granted {} karma with {} approvals", amount, approvers.len());
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции MigrateAccount
+fn process_migrate_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    msg!("Entering process_migrate_account");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let payer = next_account_info(accounts_iter)?; // Кто вносит недостающую ренту (подписывает)
+    let user_pda = next_account_info(accounts_iter)?; // PDA аккаунт пользователя
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для перевода
+    let owner_wallet = next_account_info(accounts_iter).ok(); // Кошелёк владельца (для схем без поля owner)
+
+    if !payer.is_signer {
+        msg!("Payer account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if user_pda.owner != program_id {
+        msg!("User PDA {} is not owned by this program", user_pda.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // До версии 6 поля version не было: такие аккаунты определяем по длине (до появления
+    // дискриминатора его нет и в данных). Начиная с 6 данные старой схемы — начало текущего
+    // формата, их длина должна совпадать с версией
+    let data_len = user_pda.data_len();
+    let unversioned_layout = UnversionedUserLayout::from_len(data_len);
+    let discriminator_expected = !matches!(unversioned_layout, Some(layout) if !layout.has_discriminator());
+    if discriminator_expected && !UserAccount::has_discriminator(&user_pda.data.borrow()) {
+        msg!("Account {} is not a user account", user_pda.key);
+        return Err(VertaError::InvalidAccountDiscriminator.into());
+    }
+    let version = user_pda.data.borrow().get(UserAccount::VERSION_OFFSET).copied().unwrap_or(0);
+    let account_data: UserAccount = if let Some(layout) = unversioned_layout {
+        let mut account_data: UserAccount = layout.decode(&user_pda.data.borrow())?.into();
+        if !layout.has_owner() {
+            // Кошелька в данных нет: его передаёт последний аккаунт, адрес проверяется по сидам
+            // [b"user", кошелёк], под которыми создавались такие аккаунты
+            let owner_wallet = owner_wallet.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let (legacy_pda, bump) = Pubkey::find_program_address(&[b"user", owner_wallet.key.as_ref()], program_id);
+            if legacy_pda != *user_pda.key {
+                msg!("User PDA {} was not derived from wallet {}", user_pda.key, owner_wallet.key);
+                return Err(VertaError::InvalidPda.into());
+            }
+            account_data.owner = *owner_wallet.key;
+            account_data.bump = bump;
+            // Время регистрации не сохранялось: считаем им момент миграции
+            let now = Clock::get()?.unix_timestamp;
+            account_data.registered_at = now;
+            account_data.last_updated = now;
+        }
+        account_data.upgrade_from(5);
+        account_data
+    } else if version == USER_ACCOUNT_SCHEMA_VERSION && data_len >= UserAccount::LEN {
+        msg!("User account {} is already at schema version {}", user_pda.key, USER_ACCOUNT_SCHEMA_VERSION);
+        return Ok(());
+    } else if user_account_len(version) == Some(data_len) {
+        // Новые поля дополняем нулями, затем заполняем по правилам схемы
+        let mut data = user_pda.data.borrow().to_vec();
+        data.resize(UserAccount::LEN, 0);
+        let mut account_data = UserAccount::try_from_account_data(&data)?;
+        account_data.upgrade_from(version);
+        account_data
+    } else {
+        msg!("User account {} has an unsupported layout: {} bytes", user_pda.key, data_len);
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    let expected_pda =
+        Pubkey::create_program_address(&[b"user", account_data.owner.as_ref(), &[account_data.bump]], program_id)
+            .map_err(|_| VertaError::InvalidPda)?;
+    if expected_pda != *user_pda.key {
+        msg!("User PDA {} does not match its stored owner {}", user_pda.key, account_data.owner);
+        return Err(VertaError::InvalidPda.into());
+    }
+
+    // Доплачиваем ренту за новый размер (с тем же запасом, что и при регистрации)
+    let rent_required = Rent::get()?.minimum_balance(UserAccount::LEN + RENT_HEADROOM_BYTES);
+    let shortfall = rent_required.saturating_sub(user_pda.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, user_pda.key, shortfall),
+            &[payer.clone(), user_pda.clone(), system_program.clone()],
+        )?;
+    }
+
+    user_pda.realloc(UserAccount::LEN, false)?;
+    account_data.write_account_data(&mut user_pda.data.borrow_mut())?;
+
+    msg!(
+        "User account {} migrated to schema version {} ({} lamports paid by {})",
+        user_pda.key,
+        USER_ACCOUNT_SCHEMA_VERSION,
+        shortfall,
+        payer.key
+    );
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Обработчик инструкции Endorse
+fn process_endorse(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    weight: u64,
+) -> ProgramResult {
+    msg!("Entering process_endorse");
+
+    let accounts_iter = &mut accounts.iter();
+
+    // Требуемые аккаунты
+    let endorser = next_account_info(accounts_iter)?; // Поручитель (подписывает и платит за запись)
+    let endorser_pda = next_account_info(accounts_iter)?; // PDA аккаунт поручителя
+    let endorsee_pda = next_account_info(accounts_iter)?; // PDA аккаунт получателя
+    let config_pda = next_account_info(accounts_iter)?; // PDA глобальной конфигурации
+    let endorsement_pda = next_account_info(accounts_iter)?; // PDA записи поручительства
+    let reverse_endorsement_pda = next_account_info(accounts_iter)?; // PDA встречного поручительства (может не существовать)
+    let system_program = next_account_info(accounts_iter)?; // Системная программа для создания аккаунта
+    let stats_pda = next_account_info(accounts_iter)?; // PDA глобальной статистики
+    let payer = optional_payer(accounts_iter, endorser)?; // Плательщик ренты записи
+
+    if !endorser.is_signer {
+        msg!("Endorser account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if weight == 0 || weight > MAX_ENDORSEMENT_WEIGHT {
+        msg!("Endorsement weight {} is outside 1..={}", weight, MAX_ENDORSEMENT_WEIGHT);
+        return Err(VertaError::InvalidEndorsementWeight.into());
+    }
+
+    let config = load_config(program_id, config_pda)?;
+    let mut endorser_data = load_user_account(program_id, endorser_pda)?;
+    if endorser_data.owner != *endorser.key {
+        msg!("Signer {} does not own user account {}", endorser.key, endorser_pda.key);
+        return Err(VertaError::Unauthorized.into());
+    }
+    if endorser_data.frozen {
+        msg!("Frozen users cannot endorse");
+        return Err(VertaError::AccountFrozen.into());
+    }
+    if endorser_data.level < MIN_ENDORSER_LEVEL {
+        msg!("Endorser must be at least level {}", MIN_ENDORSER_LEVEL);
+        return Err(VertaError::Unauthorized.into());
+    }
+    let mut endorsee_data = load_user_account(program_id, endorsee_pda)?;
+    let endorsee = endorsee_data.owner;
+    if endorsee == *endorser.key {
+        msg!("Users cannot endorse themselves");
+        return Err(VertaError::SelfEndorsement.into());
+    }
+
+    // Лимит суммарного веса поручительств за эпоху: иначе одна учётная запись
+    // раздаёт карму без ограничений
+    let epoch = Clock::get()?.epoch;
+    if endorser_data.endorsement_epoch != epoch {
+        endorser_data.endorsement_epoch = epoch;
+        endorser_data.endorsed_in_epoch = 0;
+    }
+    let endorsed_in_epoch = endorser_data.endorsed_in_epoch.saturating_add(weight);
+    let budget = config.params.endorsement_epoch_budget;
+    if budget > 0 && endorsed_in_epoch > budget {
+        msg!(
+            "Endorsement budget exceeded: {} of {} already used in epoch {}",
+            endorser_data.endorsed_in_epoch,
+            budget,
+            epoch
+        );
+        return Err(VertaError::EndorsementBudgetExceeded.into());
+    }
+    endorser_data.endorsed_in_epoch = endorsed_in_epoch;
+    store_user_account(endorser_pda, &mut endorser_data)?;
+
+    // Проверка PDA записи поручительства
+    let (expected_endorsement_pda, bump) =
+        Pubkey::find_program_address(&[b"endorsement", endorser.key.as_ref(), endorsee.as_ref()], program_id);
+    if expected_endorsement_pda != *endorsement_pda.key {
+        msg!("Invalid endorsement PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    // Повторное поручительство дописывается в существующую запись
+    let previous = if endorsement_pda.data_is_empty() {
+        None
+    } else {
+        if endorsement_pda.owner != program_id {
+            msg!("Endorsement PDA is not owned by this program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Some(EndorsementAccount::try_from_account_data(&endorsement_pda.data.borrow())?)
+    };
+    let count = previous.map_or(1, |record| record.endorsements().saturating_add(1));
+
+    // Встречное поручительство: пара, поручающаяся друг за друга, получает меньше кармы
+    let (expected_reverse_pda, _) =
+        Pubkey::find_program_address(&[b"endorsement", endorsee.as_ref(), endorser.key.as_ref()], program_id);
+    if expected_reverse_pda != *reverse_endorsement_pda.key {
+        msg!("Invalid reverse endorsement PDA address provided");
+        return Err(VertaError::InvalidPda.into());
+    }
+    let mutual = reverse_endorsement_pda.owner == program_id
+        && EndorsementAccount::has_discriminator(&reverse_endorsement_pda.data.borrow());
+    let karma_awarded = endorsement_karma(weight, count, mutual);
+    if mutual || count > 1 {
+        msg!("Endorsement #{} (mutual: {}): {} karma reduced to {}", count, mutual, weight, karma_awarded);
+    }
+
+    if karma_awarded > 0 {
+        credit_karma(
+            program_id,
+            &config,
+            &mut endorsee_data,
+            stats_pda,
+            karma_awarded,
+            KARMA_CATEGORY_COMMUNITY,
+            REASON_NONE,
+        )?;
+        store_user_account(endorsee_pda, &mut endorsee_data)?;
+    }
+
+    let space = EndorsementAccount::LEN;
+    let rent_required = Rent::get()?.minimum_balance(space);
+    let endorsement_data = match previous {
+        Some(record) => {
+            // Запись старой схемы расширяется до текущей; недостающую ренту вносит плательщик
+            if endorsement_pda.data_len() < space {
+                let shortfall = rent_required.saturating_sub(endorsement_pda.lamports());
+                if shortfall > 0 {
+                    invoke(
+                        &system_instruction::transfer(payer.key, endorsement_pda.key, shortfall),
+                        &[payer.clone(), endorsement_pda.clone(), system_program.clone()],
+                    )?;
+                }
+                endorsement_pda.realloc(space, false)?;
+            }
+            EndorsementAccount {
+                weight: record.weight.saturating_add(weight),
+                karma_awarded: record.karma_awarded.saturating_add(karma_awarded),
+                mutual,
+                endorsed_at: Clock::get()?.unix_timestamp,
+                count,
+                ..record
+            }
+        }
+        None => {
+            // Создаем запись о поручительстве за счет плательщика
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    endorsement_pda.key,
+                    rent_required,
+                    space as u64,
+                    program_id,
+                ),
+                &[payer.clone(), endorsement_pda.clone(), system_program.clone()],
+                &[&[b"endorsement", endorser.key.as_ref(), endorsee.as_ref(), &[bump]]],
+            )?;
+            EndorsementAccount {
+                endorser: *endorser.key,
+                endorsee,
+                weight,
+                karma_awarded,
+                mutual,
+                endorsed_at: Clock::get()?.unix_timestamp,
+                count,
+            }
+        }
+    };
+    endorsement_data.write_account_data(&mut endorsement_pda.data.borrow_mut())?;
+
+    msg!(
+        "{} endorsed {} with weight {} (endorsement #{}), {} karma awarded",
+        endorser.key,
+        endorsee,
+        weight,
+        count,
+        karma_awarded
+    );
+
+    Ok(()) // Успешное выполнение инструкции
+}
+
+// Подпись, проверенная программой Ed25519: (ключ, подпись, сообщение)
+type VerifiedSignature = (Pubkey, [u8; 64], Vec<u8>);
+
+// Подписи, проверенные инструкциями программы Ed25519 текущей транзакции. Учитываются только подписи, данные которых
+// лежат в самой инструкции Ed25519. Если проверка подписи не прошла, транзакция
+// завершается ошибкой ещё до вызова программы.
+fn verified_ed25519_signatures(
+    instructions_sysvar: &AccountInfo,
+) -> Result<Vec<VerifiedSignature>, ProgramError> {
+    if *instructions_sysvar.key != sysvar::instructions::id() {
+        msg!("Invalid instructions sysvar account");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Смещения одной подписи (Ed25519SignatureOffsets): 7 полей u16
+    const OFFSETS_LEN: usize = 14;
+    const OFFSETS_START: usize = 2;
+    const CURRENT_INSTRUCTION: usize = u16::MAX as usize;
+
+    let mut verified = Vec::new();
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if instruction.program_id != ed25519_program::id() {
+            continue;
+        }
+        let data = &instruction.data;
+        let count = data.first().copied().unwrap_or(0) as usize;
+        for i in 0..count {
+            let start = OFFSETS_START + i * OFFSETS_LEN;
+            let Some(offsets) = data.get(start..start + OFFSETS_LEN) else {
+                break;
+            };
+            let field = |n: usize| u16::from_le_bytes([offsets[2 * n], offsets[2 * n + 1]]) as usize;
+            let (signature_offset, signature_ix, pubkey_offset, pubkey_ix) = (field(0), field(1), field(2), field(3));
+            let (message_offset, message_size, message_ix) = (field(4), field(5), field(6));
+            if signature_ix != CURRENT_INSTRUCTION || pubkey_ix != CURRENT_INSTRUCTION || message_ix != CURRENT_INSTRUCTION {
+                continue;
+            }
+            let (Some(signature), Some(pubkey), Some(message)) = (
+                data.get(signature_offset..signature_offset + 64),
+                data.get(pubkey_offset..pubkey_offset + 32),
+                data.get(message_offset..message_offset + message_size),
+            ) else {
+                continue;
+            };
+            let mut signature_bytes = [0u8; 64];
+            signature_bytes.copy_from_slice(signature);
+            let pubkey = Pubkey::try_from(pubkey).map_err(|_| ProgramError::InvalidInstructionData)?;
+            verified.push((pubkey, signature_bytes, message.to_vec()));
+        }
+    }
+    Ok(verified)
+}
+
+// TODO: Добавить другие функции-обработчики по мере необходимости
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use std::sync::Once;
+
+    // Время, которое возвращает Clock::get() в тестах
+    const NOW: i64 = 1_700_000_000;
+
+    struct TestSyscalls;
+
+    impl SyscallStubs for TestSyscalls {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock {
+                unix_timestamp: NOW,
+                ..Clock::default()
+            };
+            // Заглушка пишет сисвар по адресу, который передаёт Clock::get()
+            unsafe { *(var_addr as *mut Clock) = clock };
+            solana_program::entrypoint::SUCCESS
+        }
+    }
+
+    fn setup() {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(TestSyscalls));
+        });
+    }
+
+    // Конфигурация с линейной кривой `karma_per_level` и без лимита скорости
+    fn config(karma_per_level: u64) -> ConfigAccount {
+        let mut config = ConfigAccount::default();
+        config.params.level_curve_base = karma_per_level;
+        config.params.karma_velocity_limit = 0;
+        config
+    }
+
+    // Пользователь, окно скорости которого относится к текущему дню
+    fn user() -> UserAccount {
+        let mut user = UserAccount::builder().build();
+        user.window_day = day_index(NOW);
+        user
+    }
+
+    // Зачисляет карму без созданной глобальной статистики (изменения в ней не учитываются)
+    fn credit(config: &ConfigAccount, user: &mut UserAccount, amount: u64, category: u8) -> ProgramResult {
+        let program_id = Pubkey::new_unique();
+        let (stats, _) = Pubkey::find_program_address(&[b"stats"], &program_id);
+        let mut lamports = 0;
+        let mut data: [u8; 0] = [];
+        let stats_pda = AccountInfo::new(&stats, false, true, &mut lamports, &mut data, &program_id, false, 0);
+        credit_karma(&program_id, config, user, &stats_pda, amount, category, REASON_NONE)
+    }
+
+    #[test]
+    fn credit_karma_window_overflow() {
+        setup();
+        let mut user = user();
+        user.karma_window[0] = u64::MAX;
+        let result = credit(&config(1_000), &mut user, 1, KARMA_CATEGORY_CODE);
+        assert_eq!(result, Err(VertaError::KarmaOverflow.into()));
+    }
+
+    #[test]
+    fn credit_karma_window_total_saturates() {
+        setup();
+        let mut config = config(1_000);
+        config.params.karma_velocity_limit = u64::MAX - 1;
+        // Сумма окна насыщается до u64::MAX, а не переполняется в малое число
+        let mut user = user();
+        user.karma_window = [u64::MAX; WINDOW_DAYS];
+        let result = credit(&config, &mut user, 1, KARMA_CATEGORY_CODE);
+        assert_eq!(result, Err(VertaError::VelocityLimitExceeded.into()));
+    }
+
+    #[test]
+    fn credit_karma_category_overflow() {
+        setup();
+        let mut user = user();
+        user.category_karma[KARMA_CATEGORY_DOCS as usize] = u64::MAX;
+        let result = credit(&config(1_000), &mut user, 1, KARMA_CATEGORY_DOCS);
+        assert_eq!(result, Err(VertaError::KarmaOverflow.into()));
+    }
+
+    #[test]
+    fn credit_karma_saturates_at_cap() {
+        setup();
+        // Порог максимального уровня насыщается до u64::MAX
+        let config = config(u64::MAX);
+        let mut user = user();
+        user.karma = u64::MAX - 1;
+        credit(&config, &mut user, 10, KARMA_CATEGORY_CODE).unwrap();
+        assert_eq!(user.karma, u64::MAX);
+        assert_eq!(user.overflow_karma, 9);
+
+        credit(&config, &mut user, 10, KARMA_CATEGORY_CODE).unwrap();
+        assert_eq!(user.karma, u64::MAX);
+        assert_eq!(user.overflow_karma, 19);
+    }
+
+    #[test]
+    fn credit_karma_overflow_karma() {
+        setup();
+        let config = config(1);
+        let cap = config.level_curve().karma_for_level(config.params.max_level);
+        let mut user = user();
+        user.karma = cap;
+        user.overflow_karma = u64::MAX - 1;
+        credit(&config, &mut user, 1, KARMA_CATEGORY_CODE).unwrap();
+        assert_eq!(user.karma, cap);
+        assert_eq!(user.overflow_karma, u64::MAX);
+
+        let result = credit(&config, &mut user, 1, KARMA_CATEGORY_CODE);
+        assert_eq!(result, Err(VertaError::KarmaOverflow.into()));
+    }
+
+    // Адрес PDA и аккаунт зарегистрированного пользователя
+    fn registered(program_id: &Pubkey) -> (Pubkey, UserAccount) {
+        let wallet = Pubkey::new_unique();
+        let (pda, bump) = Pubkey::find_program_address(&[b"user", wallet.as_ref()], program_id);
+        (pda, UserAccount::builder().owner(wallet).bump(bump).build())
+    }
+
+    fn account_data<T: VertaAccount>(account: &T, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        account.write_account_data(&mut data).unwrap();
+        data
+    }
+
+    fn load(program_id: &Pubkey, key: &Pubkey, owner: &Pubkey, data: &mut [u8]) -> Result<UserAccount, ProgramError> {
+        let mut lamports = 0;
+        let account = AccountInfo::new(key, false, true, &mut lamports, data, owner, false, 0);
+        load_user_account(program_id, &account)
+    }
+
+    #[test]
+    fn load_user_account_current_layout() {
+        let program_id = Pubkey::new_unique();
+        let (pda, user) = registered(&program_id);
+        let mut data = account_data(&user, UserAccount::LEN);
+        assert_eq!(load(&program_id, &pda, &program_id, &mut data), Ok(user));
+    }
+
+    #[test]
+    fn load_user_account_rejects_invalid_accounts() {
+        let program_id = Pubkey::new_unique();
+        let (pda, user) = registered(&program_id);
+        let data = account_data(&user, UserAccount::LEN);
+        let mut wrong_discriminator = data.clone();
+        wrong_discriminator[..DISCRIMINATOR_LEN].copy_from_slice(&ContributionAccount::DISCRIMINATOR);
+        let (other_pda, _) = registered(&program_id);
+
+        // (адрес, владелец, данные, ошибка)
+        let cases: [(Pubkey, Pubkey, Vec<u8>, ProgramError); 6] = [
+            (pda, program_id, Vec::new(), VertaError::AccountNotRegistered.into()),
+            (pda, Pubkey::new_unique(), data.clone(), ProgramError::IncorrectProgramId),
+            (pda, program_id, data[..DISCRIMINATOR_LEN / 2].to_vec(), VertaError::InvalidAccountDiscriminator.into()),
+            (pda, program_id, data[..UserAccount::LEN / 2].to_vec(), VertaError::AccountMigrationRequired.into()),
+            (pda, program_id, wrong_discriminator, VertaError::InvalidAccountDiscriminator.into()),
+            (other_pda, program_id, data, VertaError::InvalidPda.into()),
+        ];
+        for (key, owner, mut data, error) in cases {
+            assert_eq!(load(&program_id, &key, &owner, &mut data), Err(error));
+        }
+    }
+
+    #[test]
+    fn load_user_account_requires_migration() {
+        let program_id = Pubkey::new_unique();
+        let (pda, user) = registered(&program_id);
+
+        // Схема 5: без поля version
+        let v5 = UserAccountV5 {
+            owner: user.owner,
+            bump: user.bump,
+            ..UserAccountV5::default()
+        };
+        let mut data = account_data(&v5, UserAccountV5::LEN);
+        assert_eq!(load(&program_id, &pda, &program_id, &mut data), Err(VertaError::AccountMigrationRequired.into()));
+
+        // Схема до появления дискриминатора
+        let mut data = vec![0; UnversionedUserLayout::Overflow.data_len()];
+        assert_eq!(load(&program_id, &pda, &program_id, &mut data), Err(VertaError::AccountMigrationRequired.into()));
+
+        // Схемы 6–11: начало текущего формата
+        for (version, len) in [(6, USER_ACCOUNT_V6_LEN), (7, USER_ACCOUNT_V7_LEN), (8, USER_ACCOUNT_V8_LEN), (9, USER_ACCOUNT_V9_LEN), (10, USER_ACCOUNT_V10_LEN), (11, USER_ACCOUNT_V11_LEN), (12, USER_ACCOUNT_V12_LEN), (13, USER_ACCOUNT_V13_LEN)] {
+            let old = UserAccount { version, ..user.clone() };
+            let mut data = account_data(&old, UserAccount::LEN);
+            data.truncate(len);
+            assert_eq!(load(&program_id, &pda, &program_id, &mut data), Err(VertaError::AccountMigrationRequired.into()));
+        }
+
+        // Текущая длина, но устаревшая версия
+        let outdated = UserAccount { version: USER_ACCOUNT_SCHEMA_VERSION - 1, ..user };
+        let mut data = account_data(&outdated, UserAccount::LEN);
+        assert_eq!(load(&program_id, &pda, &program_id, &mut data), Err(VertaError::AccountMigrationRequired.into()));
+
+    }
+
+    #[test]
+    fn upgrade_from_marks_reached_levels_awarded() {
+        let mut user = UserAccount::builder().level(9).last_updated(NOW).build();
+        user.upgrade_from(8);
+        assert_eq!(user.version, USER_ACCOUNT_SCHEMA_VERSION);
+        assert_eq!(user.rewarded_level, 9);
+        assert!((1..=9).all(|level| user.has_badge(level)));
+        assert!(!user.has_badge(10));
+        assert!(!user.has_badge(u8::MAX));
+
+        // Поля, которые уже были в схеме, не меняются
+        let mut user = UserAccount::builder().level(9).rewarded_level(7).build();
+        user.upgrade_from(9);
+        assert_eq!(user.rewarded_level, 7);
+        assert!(user.has_badge(9));
+        user.upgrade_from(USER_ACCOUNT_SCHEMA_VERSION);
+        assert_eq!(user.rewarded_level, 7);
+    }
+
+    #[test]
+    fn upgrade_from_assigns_rent_to_owner() {
+        let owner = Pubkey::new_unique();
+        let mut user = UserAccount::builder().owner(owner).build();
+        user.upgrade_from(11);
+        assert_eq!(user.rent_payer, owner);
+
+        // Спонсор, сохранённый текущей схемой, не меняется
+        let sponsor = Pubkey::new_unique();
+        let mut user = UserAccount::builder().owner(owner).rent_payer(sponsor).build();
+        user.upgrade_from(USER_ACCOUNT_SCHEMA_VERSION);
+        assert_eq!(user.rent_payer, sponsor);
+    }
+
+    #[test]
+    fn unversioned_user_layouts_decode() {
+        let full = UserAccountV5 {
+            karma: 1,
+            level: 2,
+            last_level_up: 3,
+            identity_hash: [4; 32],
+            karma_window: [5; WINDOW_DAYS],
+            window_day: 6,
+            overflow_karma: 7,
+            owner: Pubkey::new_unique(),
+            bump: 8,
+            registered_at: 9,
+            last_updated: 10,
+            category_karma: [11; KARMA_CATEGORIES],
+            grant_nonce: 12,
+        };
+        // Каждая схема — предыдущая плюс дописанные в конец поля
+        let basic = UserAccountV5 { karma: 1, level: 2, ..UserAccountV5::default() };
+        let level_cooldown = UserAccountV5 { last_level_up: 3, ..basic.clone() };
+        let identity = UserAccountV5 { identity_hash: [4; 32], ..level_cooldown.clone() };
+        let karma_window = UserAccountV5 { karma_window: [5; WINDOW_DAYS], window_day: 6, ..identity.clone() };
+        let overflow = UserAccountV5 { overflow_karma: 7, ..karma_window.clone() };
+        let owner = UserAccountV5 { owner: full.owner, bump: 8, registered_at: 9, last_updated: 10, ..overflow.clone() };
+        let category_karma = UserAccountV5 { category_karma: [11; KARMA_CATEGORIES], ..owner.clone() };
+        let cases = [
+            (UnversionedUserLayout::Basic, 9, basic),
+            (UnversionedUserLayout::LevelCooldown, 17, level_cooldown),
+            (UnversionedUserLayout::Identity, 49, identity),
+            (UnversionedUserLayout::KarmaWindow, 113, karma_window),
+            (UnversionedUserLayout::Overflow, 121, overflow),
+            (UnversionedUserLayout::Owner, 170, owner.clone()),
+            (UnversionedUserLayout::Discriminator, 178, owner),
+            (UnversionedUserLayout::CategoryKarma, 210, category_karma),
+            (UnversionedUserLayout::GrantNonce, 218, full.clone()),
+        ];
+
+        let with_discriminator = account_data(&full, UserAccountV5::LEN);
+        for (layout, len, expected) in cases {
+            assert_eq!(layout.data_len(), len);
+            assert_eq!(UnversionedUserLayout::from_len(len), Some(layout));
+            let data = if layout.has_discriminator() {
+                with_discriminator[..len].to_vec()
+            } else {
+                with_discriminator[DISCRIMINATOR_LEN..DISCRIMINATOR_LEN + len].to_vec()
+            };
+            assert_eq!(layout.decode(&data), Ok(expected), "{:?}", layout);
+            assert_eq!(layout.decode(&data[..len - 1]), Err(ProgramError::InvalidAccountData));
+        }
+
+        // Схемы с дискриминатором проверяют его
+        let mut data = with_discriminator.clone();
+        data[..DISCRIMINATOR_LEN].copy_from_slice(&ContributionAccount::DISCRIMINATOR);
+        assert_eq!(
+            UnversionedUserLayout::GrantNonce.decode(&data),
+            Err(VertaError::InvalidAccountDiscriminator.into())
+        );
+
+        // Длины схем с полем version не пересекаются со схемами без него
+        for version in 6..=USER_ACCOUNT_SCHEMA_VERSION {
+            let len = user_account_len(version).unwrap();
+            assert_eq!(UnversionedUserLayout::from_len(len), None);
+        }
+    }
+
+    #[test]
+    fn upgrade_config_data_layouts() {
+        let params = ConfigParams {
+            max_karma_per_grant: 77,
+            ..ConfigParams::default()
+        };
+        let current = ConfigAccount {
+            version: CONFIG_ACCOUNT_SCHEMA_VERSION,
+            admin: Pubkey::new_unique(),
+            moderation_log_len: 5,
+            pending_authority: Some(Pubkey::new_unique()),
+            paused: true,
+            params,
+        };
+
+        // Схема 18: без поля version, параметры в середине
+        let mut data = ConfigAccount::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(current.admin.as_ref());
+        data.extend_from_slice(&borsh::to_vec(&params).unwrap()[..CONFIG_PARAMS_V18_LEN]);
+        data.extend_from_slice(&current.moderation_log_len.to_le_bytes());
+        data.extend_from_slice(&borsh::to_vec(&current.pending_authority).unwrap());
+        data.push(current.paused as u8);
+        data.resize(CONFIG_ACCOUNT_V18_LEN, 0);
+        assert_eq!(upgrade_config_data(&data), Ok(Some(current)));
+
+        // Схема 19: без endorsement_epoch_budget, он получает значение по умолчанию
+        let old = ConfigAccount {
+            version: 19,
+            params: ConfigParams {
+                endorsement_epoch_budget: 0,
+                ..params
+            },
+            ..current
+        };
+        let mut data = account_data(&old, ConfigAccount::LEN);
+        data.truncate(CONFIG_ACCOUNT_V19_LEN);
+        assert_eq!(upgrade_config_data(&data), Ok(Some(current)));
+
+        // Схема 20: без параметров предупреждений
+        let old = ConfigAccount {
+            version: 20,
+            params: ConfigParams {
+                strike_freeze_threshold: 0,
+                strike_probation_secs: 0,
+                ..params
+            },
+            ..current
+        };
+        let mut data = account_data(&old, ConfigAccount::LEN);
+        data.truncate(CONFIG_ACCOUNT_V20_LEN);
+        assert_eq!(upgrade_config_data(&data), Ok(Some(current)));
+
+        // Текущая схема не обновляется
+        let data = account_data(&current, ConfigAccount::LEN);
+        assert_eq!(upgrade_config_data(&data), Ok(None));
+
+        // Неизвестная длина и чужой дискриминатор
+        let mut data = account_data(&current, ConfigAccount::LEN);
+        data.truncate(ConfigAccount::LEN - 2);
+        assert_eq!(upgrade_config_data(&data), Err(ProgramError::InvalidAccountData));
+        let mut data = account_data(&current, ConfigAccount::LEN);
+        data[..DISCRIMINATOR_LEN].copy_from_slice(&UserAccount::DISCRIMINATOR);
+        assert_eq!(upgrade_config_data(&data), Err(VertaError::InvalidAccountDiscriminator.into()));
+    }
+
+    #[test]
+    fn strikes_expire_after_probation() {
+        let user = UserAccount::builder().strikes(2).last_strike_at(1_000).build();
+        assert_eq!(user.active_strikes(1_099, 100), 2);
+        assert_eq!(user.active_strikes(1_100, 100), 0);
+        // Без испытательного срока предупреждения не сгорают
+        assert_eq!(user.active_strikes(i64::MAX, 0), 2);
+    }
+
+    #[test]
+    fn endorsement_repeats_and_legacy_records() {
+        assert_eq!(endorsement_karma(100, 1, false), 100);
+        assert_eq!(endorsement_karma(100, 2, false), 50);
+        assert_eq!(endorsement_karma(100, 3, true), 33 / MUTUAL_ENDORSEMENT_DIVISOR);
+        // count = 0 в старых записях — одно поручительство
+        assert_eq!(endorsement_karma(100, 0, false), 100);
+
+        let record = EndorsementAccount {
+            endorser: Pubkey::new_unique(),
+            endorsee: Pubkey::new_unique(),
+            weight: 40,
+            karma_awarded: 40,
+            mutual: false,
+            endorsed_at: 1_700_000_000,
+            count: 3,
+        };
+        let data = account_data(&record, EndorsementAccount::LEN);
+        assert_eq!(EndorsementAccount::try_from_account_data(&data).unwrap(), record);
+        let legacy = EndorsementAccount::try_from_account_data(&data[..ENDORSEMENT_ACCOUNT_V1_LEN]).unwrap();
+        assert_eq!(legacy, EndorsementAccount { count: 0, ..record });
+        assert_eq!(legacy.endorsements(), 1);
+    }
+
+    #[test]
+    fn program_info_len_matches_borsh() {
+        let info = ProgramInfo::current();
+        let bytes = info.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), ProgramInfo::LEN);
+        assert_eq!(ProgramInfo::try_from_slice(&bytes).unwrap(), info);
+    }
+}
@@ -1,42 +1,411 @@
-use solana_client::rpc_client::RpcClient;
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
 use solana_sdk::{
-    commitment_config::CommitmentConfig,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{keypair_from_seed, read_keypair_file, write_keypair_file, Keypair, Signer},
     system_instruction,
-    transaction::Transaction,
 };
-use std::str::FromStr;
+use std::{
+    io::BufRead,
+    path::Path,
+    process::Command,
+    str::FromStr,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    sync::Mutex,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use verta_math::{decay::decay, velocity::SECONDS_PER_DAY};
+use verta_project_v2::sdk::{fixtures::Scenario, instruction, verify::executable_hash, VertaClient};
+
+// Исполняемый файл программы после `solana-verify build`
+const DEFAULT_PROGRAM_SO: &str = "target/deploy/verta_project_v2.so";
+
+// Значение флага вида `--name <value>`: None, если флага нет, ошибка, если нет значения
+fn flag_value<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    let Some(position) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    args.get(position + 1)
+        .map(|value| Some(value.as_str()))
+        .ok_or_else(|| format!("{} requires a value", name))
+}
+
+// Обязательный флаг `--name <value>`, разобранный в T
+fn required_flag<T: FromStr>(args: &[String], name: &str) -> Result<T, String> {
+    let value = flag_value(args, name)?.ok_or_else(|| format!("missing required flag {}", name))?;
+    T::from_str(value).map_err(|_| format!("invalid value for {}: {}", name, value))
+}
+
+// Разбирает флаг `--commitment <processed|confirmed|finalized>` (по умолчанию confirmed)
+fn parse_commitment(args: &[String]) -> Result<CommitmentConfig, String> {
+    let Some(value) = flag_value(args, "--commitment")? else {
+        return Ok(CommitmentConfig::confirmed());
+    };
+    let commitment = CommitmentLevel::from_str(value)
+        .map_err(|_| format!("invalid commitment level: {}", value))?;
+    Ok(CommitmentConfig { commitment })
+}
+
+// ID задеплоенной программы из переменной окружения VERTA_PROGRAM_ID. Без него все адреса PDA
+// выводились бы от нулевого ключа, поэтому отсутствие или неверное значение — ошибка.
+fn program_id_from_env() -> Result<Pubkey, String> {
+    let id = std::env::var("VERTA_PROGRAM_ID")
+        .map_err(|_| "VERTA_PROGRAM_ID is not set; export the ID of the deployed Verta program".to_string())?;
+    Pubkey::from_str(id.trim()).map_err(|_| format!("VERTA_PROGRAM_ID is not a valid program ID: {}", id))
+}
+
+// `simulate level --karma X` / `simulate decay <wallet> --days N`: локальный расчёт
+// по конфигурации из сети и тем же формулам, что и в программе
+fn simulate(client: &VertaClient, args: &[String]) -> Result<(), String> {
+    let config = client.get_config().map_err(|err| err.to_string())?;
+    match args.first().map(String::as_str) {
+        Some("level") => {
+            let karma = flag_value(args, "--karma")?
+                .ok_or("simulate level requires --karma <amount>")?;
+            let karma = u64::from_str(karma).map_err(|_| format!("invalid karma amount: {}", karma))?;
+            let level = config.level_for_karma(karma);
+            println!("Karma: {}", karma);
+            println!("Level: {}", level);
+            if level < config.params.max_level {
+                let next_level_karma = config.level_curve().karma_for_level(level + 1);
+                println!("Karma to next level: {}", next_level_karma.saturating_sub(karma));
+            } else {
+                println!("Max level reached");
+            }
+            Ok(())
+        }
+        Some("decay") => {
+            let wallet = args.get(1).ok_or("simulate decay requires <wallet>")?;
+            let wallet = Pubkey::from_str(wallet).map_err(|_| format!("invalid wallet: {}", wallet))?;
+            let days: u64 = required_flag(args, "--days")?;
+            let user = client.get_user(&wallet).map_err(|err| err.to_string())?;
+            // DecayKarma через N дней учтёт и время, прошедшее с последнего затухания
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|err| err.to_string())?
+                .as_secs() as i64;
+            let pending = now.saturating_sub(user.last_decay_at).max(0) as u64;
+            let elapsed = pending.saturating_add(days.saturating_mul(SECONDS_PER_DAY as u64));
+            let karma = decay(user.karma, elapsed, config.params.karma_half_life);
+            println!("Karma: {} -> {}", user.karma, karma);
+            println!("Level: {} -> {}", user.level, user.level.min(config.level_for_karma(karma)));
+            Ok(())
+        }
+        _ => Err("usage: simulate level --karma <amount>\n       simulate decay <wallet> --days <n>".to_string()),
+    }
+}
+
+// `export <add-karma|slash-karma> ...`: неподписанная транзакция для веб-кошелька
+// или мультисига; JSON с транзакцией и её описанием выводится в stdout
+fn export(client: &VertaClient, args: &[String]) -> Result<(), String> {
+    let program_id = client.program_id();
+    let signer: Pubkey = required_flag(args, "--signer")?;
+    let wallet: Pubkey = required_flag(args, "--wallet")?;
+    let amount: u64 = required_flag(args, "--amount")?;
+    let (ix, intent) = match args.first().map(String::as_str) {
+        Some("add-karma") => {
+            let category = flag_value(args, "--category")?.unwrap_or("0");
+            let category = u8::from_str(category).map_err(|_| format!("invalid karma category: {}", category))?;
+            let reason = flag_value(args, "--reason")?.unwrap_or("0");
+            let reason = u16::from_str(reason).map_err(|_| format!("invalid reason code: {}", reason))?;
+            (
+                instruction::add_karma(program_id, &signer, &wallet, amount, category, reason),
+                format!("Add {} karma to {} (category {}, reason code {})", amount, wallet, category, reason),
+            )
+        }
+        Some("slash-karma") => {
+            let reason_code: u16 = required_flag(args, "--reason")?;
+            let log_index = client.get_config().map_err(|err| err.to_string())?.moderation_log_len;
+            (
+                instruction::slash_karma(program_id, &signer, &wallet, amount, reason_code, [0; 32], log_index),
+                format!("Slash {} karma from {} (reason code {})", amount, wallet, reason_code),
+            )
+        }
+        _ => {
+            return Err("usage: export <add-karma|slash-karma> --signer <pubkey> --wallet <pubkey> --amount <karma> [--category <id>] [--reason <code>]".to_string())
+        }
+    };
+    let payload = client
+        .export_transaction(&[ix], &signer, &intent)
+        .map_err(|err| err.to_string())?;
+    println!("{}", payload.to_json());
+    Ok(())
+}
+
+// `dev fixtures --scenario <file.toml> --authority <keypair.json> [--url <rpc>]`:
+// воспроизводимое состояние на чистом localnet; выводит имена и адреса созданных пользователей
+fn dev(program_id: Pubkey, commitment: CommitmentConfig, args: &[String]) -> Result<(), String> {
+    if args.first().map(String::as_str) != Some("fixtures") {
+        return Err("usage: dev fixtures --scenario <file.toml> --authority <keypair.json> [--url <rpc>]".to_string());
+    }
+    let scenario_path = flag_value(args, "--scenario")?.ok_or("missing required flag --scenario")?;
+    let authority_path = flag_value(args, "--authority")?.ok_or("missing required flag --authority")?;
+    let rpc_url = flag_value(args, "--url")?.unwrap_or("http://127.0.0.1:8899");
+
+    let scenario = std::fs::read_to_string(scenario_path)
+        .map_err(|err| format!("failed to read {}: {}", scenario_path, err))?;
+    let scenario: Scenario = toml::from_str(&scenario).map_err(|err| format!("invalid scenario: {}", err))?;
+    let authority = read_keypair_file(authority_path)
+        .map_err(|err| format!("failed to read keypair {}: {}", authority_path, err))?;
+
+    let client = VertaClient::new_with_commitment(rpc_url.to_string(), commitment, program_id);
+    let users = client
+        .apply_scenario(&authority, &scenario)
+        .map_err(|err| err.to_string())?;
+    for user in users {
+        println!("{} {}", user.name, user.keypair.pubkey());
+    }
+    Ok(())
+}
+
+// `program verify [--skip-build] [--so <path>]`: собирает программу через solana-verify
+// (детерминированная сборка в docker) и сравнивает хеш с задеплоенным бинарником
+fn program(client: &VertaClient, args: &[String]) -> Result<(), String> {
+    if args.first().map(String::as_str) != Some("verify") {
+        return Err("usage: program verify [--skip-build] [--so <path>]".to_string());
+    }
+    let so_path = flag_value(args, "--so")?.unwrap_or(DEFAULT_PROGRAM_SO);
+
+    if !args.iter().any(|arg| arg == "--skip-build") {
+        let status = Command::new("solana-verify")
+            .arg("build")
+            .status()
+            .map_err(|err| format!("failed to run solana-verify (is it installed?): {}", err))?;
+        if !status.success() {
+            return Err(format!("solana-verify build failed: {}", status));
+        }
+    }
+
+    let executable = std::fs::read(so_path).map_err(|err| format!("failed to read {}: {}", so_path, err))?;
+    let local_hash = executable_hash(&executable);
+    let deployed_hash = client.deployed_program_hash().map_err(|err| err.to_string())?;
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("Program: {}", client.program_id());
+    println!("Commit: {}", commit);
+    println!("Local build hash: {}", local_hash);
+    println!("On-chain hash: {}", deployed_hash);
+    if local_hash != deployed_hash {
+        return Err("on-chain program does NOT match the source tree".to_string());
+    }
+    println!("On-chain program matches the source tree");
+    Ok(())
+}
+
+// Алфавит base58: символы вне него никогда не встретятся в адресе
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Записывает ключ в файл; существующий файл перезаписывается только с `--force`
+fn save_keypair(keypair: &Keypair, outfile: &str, force: bool) -> Result<(), String> {
+    if Path::new(outfile).exists() && !force {
+        return Err(format!("{} already exists, use --force to overwrite", outfile));
+    }
+    write_keypair_file(keypair, outfile).map_err(|err| format!("failed to write {}: {}", outfile, err))?;
+    Ok(())
+}
+
+// Проверяет, что шаблон адреса состоит из символов base58
+fn check_base58_pattern(pattern: &str, ignore_case: bool) -> Result<(), String> {
+    let invalid = pattern.chars().find(|c| {
+        if ignore_case {
+            !BASE58_ALPHABET.contains(c.to_ascii_lowercase()) && !BASE58_ALPHABET.contains(c.to_ascii_uppercase())
+        } else {
+            !BASE58_ALPHABET.contains(*c)
+        }
+    });
+    match invalid {
+        Some(c) => Err(format!("'{}' is not a base58 character, no address can contain it", c)),
+        None => Ok(()),
+    }
+}
+
+// Парольная фраза BIP39 для `keygen new`. В аргументах её не передаём: их видят другие
+// пользователи системы (ps, /proc) и сохраняет история shell. `--passphrase` запрашивает фразу
+// в терминале дважды, `--passphrase-stdin` читает первую строку stdin (для скриптов).
+fn read_passphrase(args: &[String]) -> Result<String, String> {
+    if args.iter().any(|arg| arg == "--passphrase-stdin") {
+        let mut line = String::new();
+        std::io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .map_err(|err| format!("failed to read passphrase from stdin: {}", err))?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+    let Some(position) = args.iter().position(|arg| arg == "--passphrase") else {
+        return Ok(String::new());
+    };
+    if args.get(position + 1).is_some_and(|next| !next.starts_with("--")) {
+        return Err("--passphrase no longer takes a value; enter it at the prompt or use --passphrase-stdin".to_string());
+    }
+    let passphrase = rpassword::prompt_password("BIP39 passphrase: ")
+        .map_err(|err| format!("failed to read passphrase (use --passphrase-stdin without a terminal): {}", err))?;
+    let confirmation = rpassword::prompt_password("Repeat passphrase: ").map_err(|err| err.to_string())?;
+    if passphrase != confirmation {
+        return Err("passphrases do not match".to_string());
+    }
+    Ok(passphrase)
+}
+
+// `keygen new --outfile <path> [--words 12|24] [--passphrase | --passphrase-stdin] [--force]`:
+// ключ плательщика или админа с мнемонической фразой BIP39 для восстановления.
+// `keygen grind [--starts-with <prefix>] [--ends-with <suffix>] [--ignore-case] [--outfile <path>]`:
+// перебор ключей до адреса с нужным префиксом/суффиксом (например, для ID программы)
+fn keygen(args: &[String]) -> Result<(), String> {
+    let force = args.iter().any(|arg| arg == "--force");
+    match args.first().map(String::as_str) {
+        Some("new") => {
+            let outfile = flag_value(args, "--outfile")?.ok_or("keygen new requires --outfile <path>")?;
+            let words = match flag_value(args, "--words")? {
+                Some(words) => usize::from_str(words).map_err(|_| format!("invalid word count: {}", words))?,
+                None => 12,
+            };
+            let mnemonic_type = MnemonicType::for_word_count(words)
+                .map_err(|_| format!("unsupported word count: {} (use 12, 15, 18, 21 or 24)", words))?;
+            let passphrase = read_passphrase(args)?;
+            let mnemonic = Mnemonic::new(mnemonic_type, Language::English);
+            // Тот же способ вывода ключа из фразы, что и в solana-keygen
+            let seed = Seed::new(&mnemonic, &passphrase);
+            let keypair = keypair_from_seed(seed.as_bytes()).map_err(|err| err.to_string())?;
+            save_keypair(&keypair, outfile, force)?;
+            println!("Wrote keypair to {}", outfile);
+            println!("Pubkey: {}", keypair.pubkey());
+            println!("Save this seed phrase to recover the keypair:");
+            println!("{}", mnemonic.phrase());
+            Ok(())
+        }
+        Some("grind") => {
+            let ignore_case = args.iter().any(|arg| arg == "--ignore-case");
+            let starts_with = flag_value(args, "--starts-with")?.unwrap_or("");
+            let ends_with = flag_value(args, "--ends-with")?.unwrap_or("");
+            if starts_with.is_empty() && ends_with.is_empty() {
+                return Err("keygen grind requires --starts-with and/or --ends-with".to_string());
+            }
+            check_base58_pattern(starts_with, ignore_case)?;
+            check_base58_pattern(ends_with, ignore_case)?;
+            let normalize = |s: &str| if ignore_case { s.to_lowercase() } else { s.to_string() };
+            let (starts_with, ends_with) = (normalize(starts_with), normalize(ends_with));
+
+            let found = AtomicBool::new(false);
+            let attempts = AtomicU64::new(0);
+            let result = Mutex::new(None);
+            let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            thread::scope(|scope| {
+                for _ in 0..threads {
+                    scope.spawn(|| {
+                        while !found.load(Ordering::Relaxed) {
+                            let keypair = Keypair::new();
+                            let address = normalize(&keypair.pubkey().to_string());
+                            let count = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                            if count.is_multiple_of(1_000_000) {
+                                eprintln!("Searched {} keypairs...", count);
+                            }
+                            if address.starts_with(&starts_with)
+                                && address.ends_with(&ends_with)
+                                && !found.swap(true, Ordering::Relaxed)
+                            {
+                                *result.lock().unwrap() = Some(keypair);
+                            }
+                        }
+                    });
+                }
+            });
+            let keypair = result.into_inner().unwrap().expect("grind stops only after a match");
+            let outfile = match flag_value(args, "--outfile")? {
+                Some(outfile) => outfile.to_string(),
+                None => format!("{}.json", keypair.pubkey()),
+            };
+            save_keypair(&keypair, &outfile, force)?;
+            println!("Found {} after {} keypairs", keypair.pubkey(), attempts.load(Ordering::Relaxed));
+            println!("Wrote keypair to {}", outfile);
+            Ok(())
+        }
+        _ => Err("usage: keygen new --outfile <path> [--words 12|24] [--passphrase | --passphrase-stdin] [--force]\n       \
+                  keygen grind [--starts-with <prefix>] [--ends-with <suffix>] [--ignore-case] [--outfile <path>] [--force]"
+            .to_string()),
+    }
+}
 
 #[tokio::main]
 async fn main() {
-    
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("keygen") {
+        if let Err(err) = keygen(&args[2..]) {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    let commitment = parse_commitment(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+
     let rpc_url = "https://api.devnet.solana.com".to_string();
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    // ID задеплоенной программы берём из окружения
+    let program_id = program_id_from_env().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+
+    if args.get(1).map(String::as_str) == Some("dev") {
+        if let Err(err) = dev(program_id, commitment, &args[2..]) {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    let client = VertaClient::new_with_commitment(rpc_url, commitment, program_id);
+
+    if args.get(1).map(String::as_str) == Some("simulate") {
+        if let Err(err) = simulate(&client, &args[2..]) {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("export") {
+        if let Err(err) = export(&client, &args[2..]) {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("program") {
+        if let Err(err) = program(&client, &args[2..]) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
 
-    
     let from_keypair = Keypair::new();
     let to_pubkey = Pubkey::from_str("EfNMxEv6RpJLmntFYCSYmy6nBG1NW3SJ2tMzr1cw6cL7").unwrap();
 
-    
-    let balance = client.get_balance(&from_keypair.pubkey()).unwrap();
+
+    let balance = client.rpc().get_balance(&from_keypair.pubkey()).unwrap();
     println!("Balance: {}", balance);
 
-    
-    let latest_blockhash = client.get_latest_blockhash().unwrap();
+
     let transfer_ix = system_instruction::transfer(
         &from_keypair.pubkey(),
         &to_pubkey,
         1_000_000, // 0.001 SOL
     );
 
-    let transaction = Transaction::new_signed_with_payer(
-        &[transfer_ix],
-        Some(&from_keypair.pubkey()),
-        &[&from_keypair],
-        latest_blockhash,
-    );
-
-    let signature = client.send_and_confirm_transaction(&transaction).unwrap();
+    let signature = client
+        .send_instructions(&[transfer_ix], &from_keypair, &[])
+        .unwrap();
     println!("Transaction signature: {}", signature);
-}
\ No newline at end of file
+}
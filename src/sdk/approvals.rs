@@ -0,0 +1,106 @@
+// Многоподписные начисления кармы (AddKarmaWithApprovals).
+// Одобряющие подписывают GrantMessage своими ключами, не участвуя в транзакции.
+// Отправитель собирает подписи в GrantEnvelope и кладёт перед инструкцией Verta
+// инструкцию нативной программы Ed25519, которая проверяет все подписи.
+
+use borsh::BorshSerialize;
+use solana_sdk::{
+    ed25519_program,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+
+use super::{client::VertaClient, error::Result, instruction};
+use crate::{GrantApproval, GrantEnvelope, GrantMessage};
+
+/// Сообщение, которое подписывают одобряющие.
+pub fn grant_message_bytes(message: &GrantMessage) -> Vec<u8> {
+    message
+        .try_to_vec()
+        .expect("serializing a grant message into a Vec never fails")
+}
+
+/// Подписывает сообщение ключом одобряющего.
+pub fn sign_grant_approval(cosigner: &Keypair, message: &[u8]) -> GrantApproval {
+    GrantApproval {
+        signer: cosigner.pubkey(),
+        signature: <[u8; 64]>::from(cosigner.sign_message(message)),
+    }
+}
+
+/// Инструкция программы Ed25519, проверяющая все одобрения над одним сообщением.
+/// Данные: заголовок, смещения подписей, пары (ключ, подпись), затем сообщение.
+pub fn ed25519_verify_instruction(message: &[u8], approvals: &[GrantApproval]) -> Instruction {
+    const OFFSETS_LEN: usize = 14;
+    const ENTRY_LEN: usize = 32 + 64;
+    const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+    let data_start = 2 + approvals.len() * OFFSETS_LEN;
+    let message_offset = data_start + approvals.len() * ENTRY_LEN;
+
+    let mut data = Vec::with_capacity(message_offset + message.len());
+    data.push(approvals.len() as u8);
+    data.push(0); // Выравнивание
+    for i in 0..approvals.len() {
+        let pubkey_offset = data_start + i * ENTRY_LEN;
+        let signature_offset = pubkey_offset + 32;
+        for field in [
+            signature_offset as u16,
+            CURRENT_INSTRUCTION,
+            pubkey_offset as u16,
+            CURRENT_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+    }
+    for approval in approvals {
+        data.extend_from_slice(approval.signer.as_ref());
+        data.extend_from_slice(&approval.signature);
+    }
+    data.extend_from_slice(message);
+
+    Instruction::new_with_bytes(ed25519_program::id(), &data, vec![])
+}
+
+impl VertaClient {
+    /// Сообщение для одобрения начисления `amount` кармы пользователю `wallet`
+    /// (берёт текущий grant_nonce из его аккаунта).
+    pub fn grant_message(&self, wallet: &Pubkey, amount: u64, category: u8, expires_at: i64) -> Result<GrantMessage> {
+        Ok(GrantMessage {
+            program_id: *self.program_id(),
+            user: *wallet,
+            amount,
+            category,
+            nonce: self.get_user(wallet)?.grant_nonce,
+            expires_at,
+        })
+    }
+
+    /// Начисляет карму по собранным одобрениям. `message` должно быть тем,
+    /// что подписывали одобряющие (см. `grant_message`).
+    pub fn add_karma_with_approvals(
+        &self,
+        message: &GrantMessage,
+        approvals: Vec<GrantApproval>,
+        submitter: &Keypair,
+    ) -> Result<Signature> {
+        let verify_ix = ed25519_verify_instruction(&grant_message_bytes(message), &approvals);
+        let envelope = GrantEnvelope {
+            expires_at: message.expires_at,
+            approvals,
+        };
+        let ix = instruction::add_karma_with_approvals(
+            self.program_id(),
+            &submitter.pubkey(),
+            &message.user,
+            message.amount,
+            message.category,
+            envelope,
+        );
+        self.send_instructions(&[verify_ix, ix], submitter, &[])
+    }
+}
@@ -0,0 +1,148 @@
+// Кэш аккаунтов для SDK.
+// Редко меняющиеся аккаунты (конфиг, статистика, аккаунты пользователей) не нужно
+// запрашивать у RPC при каждом обращении. Запись считается устаревшей по истечении TTL
+// или (опционально) когда текущий слот ушёл вперёд больше, чем на `max_slot_lag`.
+// После отправки транзакции затронутые аккаунты нужно явно инвалидировать.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, clock::Slot, instruction::Instruction, pubkey::Pubkey};
+
+use super::error::Result;
+
+/// Политика устаревания записей в кэше.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// Максимальное время жизни записи
+    pub ttl: Duration,
+    /// Максимальное отставание слота записи от текущего слота (None — слот не проверяется)
+    pub max_slot_lag: Option<Slot>,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(30),
+            max_slot_lag: None,
+        }
+    }
+}
+
+// Запись кэша: None в `account` означает, что аккаунт не существует (это тоже кэшируется)
+#[derive(Debug)]
+struct CacheEntry {
+    account: Option<Account>,
+    slot: Slot,
+    fetched_at: Instant,
+}
+
+/// Потокобезопасный кэш аккаунтов с TTL и учётом слотов.
+#[derive(Debug, Default)]
+pub struct AccountCache {
+    policy: CachePolicy,
+    entries: Mutex<HashMap<Pubkey, CacheEntry>>,
+}
+
+impl AccountCache {
+    pub fn new(policy: CachePolicy) -> Self {
+        Self {
+            policy,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Возвращает закэшированный аккаунт, если запись ещё свежая.
+    /// Внешний Option — есть ли попадание в кэш, внутренний — существует ли аккаунт.
+    pub fn get(&self, key: &Pubkey, current_slot: Option<Slot>) -> Option<Option<Account>> {
+        let mut entries = self.entries.lock().unwrap();
+        let fresh = self.is_fresh(entries.get(key)?, current_slot);
+        if !fresh {
+            entries.remove(key);
+            return None;
+        }
+        entries.get(key).map(|entry| entry.account.clone())
+    }
+
+    /// Сохраняет аккаунт, полученный в контексте слота `slot`.
+    /// Более старые данные не перезаписывают более новые.
+    pub fn insert(&self, key: Pubkey, account: Option<Account>, slot: Slot) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(existing) = entries.get(&key) {
+            if existing.slot > slot {
+                return;
+            }
+        }
+        entries.insert(
+            key,
+            CacheEntry {
+                account,
+                slot,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Удаляет запись для одного аккаунта.
+    pub fn invalidate(&self, key: &Pubkey) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    /// Удаляет записи всех аккаунтов, которые инструкции помечают как изменяемые.
+    /// Вызывается после отправки транзакции с этими инструкциями.
+    pub fn invalidate_writes(&self, instructions: &[Instruction]) {
+        let mut entries = self.entries.lock().unwrap();
+        for meta in instructions.iter().flat_map(|ix| ix.accounts.iter()) {
+            if meta.is_writable {
+                entries.remove(&meta.pubkey);
+            }
+        }
+    }
+
+    /// Полностью очищает кэш.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn is_fresh(&self, entry: &CacheEntry, current_slot: Option<Slot>) -> bool {
+        if entry.fetched_at.elapsed() > self.policy.ttl {
+            return false;
+        }
+        match (self.policy.max_slot_lag, current_slot) {
+            (Some(max_lag), Some(slot)) => slot.saturating_sub(entry.slot) <= max_lag,
+            _ => true,
+        }
+    }
+}
+
+/// Загружает аккаунт через кэш, если он передан, иначе напрямую из RPC.
+/// При включённой проверке слота дополнительно запрашивает текущий слот.
+pub fn get_account_cached(
+    rpc: &RpcClient,
+    cache: Option<&AccountCache>,
+    key: &Pubkey,
+) -> Result<Option<Account>> {
+    let Some(cache) = cache else {
+        return Ok(rpc.get_account_with_commitment(key, rpc.commitment())?.value);
+    };
+
+    let current_slot = match cache.policy.max_slot_lag {
+        Some(_) => Some(rpc.get_slot()?),
+        None => None,
+    };
+    if let Some(account) = cache.get(key, current_slot) {
+        return Ok(account);
+    }
+
+    let response = rpc.get_account_with_commitment(key, rpc.commitment())?;
+    cache.insert(*key, response.value.clone(), response.context.slot);
+    Ok(response.value)
+}
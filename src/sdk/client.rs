@@ -0,0 +1,582 @@
+// VertaClient — общий фасад SDK.
+// Владеет RPC-клиентом (его HTTP-соединения переиспользуются между запросами),
+// одним websocket-соединением для подписок, метаданными кластера и ID программы.
+// Клонирование дешёвое: все клоны разделяют одни и те же соединения.
+
+use std::sync::{Arc, OnceLock};
+
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_client::RpcClient};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    signer::keypair::Keypair,
+    transaction::Transaction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use tokio::sync::OnceCell;
+
+use super::{
+    cache::{get_account_cached, AccountCache, CachePolicy},
+    confirm::{send_and_confirm, ConfirmationConfig},
+    error::{Error, Result},
+    find_badge_mint_pda, find_badge_nft_mint_pda, find_challenge_pda, find_config_pda, find_contribution_pda, find_global_stats_pda, find_moderation_log_pda, find_treasury_pda, find_user_pda,
+    find_reason_codes_pda, find_shard_index_pda, find_verifier_pda, instruction,
+};
+use crate::{
+    ChallengeAccount, ConfigAccount, ConfigParams, ContributionAccount, GlobalStatsAccount, ModerationLogAccount, ReasonCodeTableAccount,
+    ShardIndexAccount, TreasuryAccount, UserAccount, VerifierAccount, VertaAccount, MAX_REASON_CODES,
+};
+
+/// Метаданные кластера, к которому подключён клиент.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClusterInfo {
+    pub rpc_url: String,
+    pub ws_url: String,
+    pub genesis_hash: Hash,
+    pub solana_core: String,
+}
+
+#[derive(Clone)]
+pub struct VertaClient {
+    rpc: Arc<RpcClient>,
+    ws_url: String,
+    pubsub: Arc<OnceCell<Arc<PubsubClient>>>,
+    cluster: Arc<OnceLock<ClusterInfo>>,
+    program_id: Pubkey,
+    cache: Option<Arc<AccountCache>>,
+    confirmation: ConfirmationConfig,
+    heap_frame: Option<u32>,
+}
+
+impl VertaClient {
+    /// Создаёт клиент с уровнем подтверждения `confirmed`.
+    pub fn new(rpc_url: impl Into<String>, program_id: Pubkey) -> Self {
+        Self::new_with_commitment(rpc_url, CommitmentConfig::confirmed(), program_id)
+    }
+
+    pub fn new_with_commitment(
+        rpc_url: impl Into<String>,
+        commitment: CommitmentConfig,
+        program_id: Pubkey,
+    ) -> Self {
+        let rpc_url = rpc_url.into();
+        let ws_url = ws_url_from_rpc_url(&rpc_url);
+        Self {
+            rpc: Arc::new(RpcClient::new_with_commitment(rpc_url, commitment)),
+            ws_url,
+            pubsub: Arc::new(OnceCell::new()),
+            cluster: Arc::new(OnceLock::new()),
+            program_id,
+            cache: None,
+            confirmation: ConfirmationConfig::default().with_commitment(commitment),
+            heap_frame: None,
+        }
+    }
+
+    /// Задаёт websocket-адрес явно (по умолчанию выводится из RPC-адреса).
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = ws_url.into();
+        self.pubsub = Arc::new(OnceCell::new());
+        self
+    }
+
+    /// Включает кэширование загружаемых аккаунтов.
+    pub fn with_cache(mut self, policy: CachePolicy) -> Self {
+        self.cache = Some(Arc::new(AccountCache::new(policy)));
+        self
+    }
+
+    /// Задаёт параметры подтверждения для всех отправляемых транзакций.
+    pub fn with_confirmation(mut self, confirmation: ConfirmationConfig) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// Добавляет в каждую транзакцию запрос кучи размером `bytes`.
+    /// Нужно для программы, собранной с фичей custom-heap (см. `ProgramInfo::features`).
+    pub fn with_heap_frame(mut self, bytes: u32) -> Self {
+        self.heap_frame = Some(bytes);
+        self
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn program_id(&self) -> &Pubkey {
+        &self.program_id
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.rpc.commitment()
+    }
+
+    pub fn confirmation(&self) -> &ConfirmationConfig {
+        &self.confirmation
+    }
+
+    pub fn cache(&self) -> Option<&AccountCache> {
+        self.cache.as_deref()
+    }
+
+    /// Возвращает общее websocket-соединение, открывая его при первом обращении.
+    pub async fn pubsub(&self) -> Result<Arc<PubsubClient>> {
+        let client = self
+            .pubsub
+            .get_or_try_init(|| async { PubsubClient::new(&self.ws_url).await.map(Arc::new) })
+            .await?;
+        Ok(client.clone())
+    }
+
+    /// Метаданные кластера (запрашиваются один раз и запоминаются).
+    pub fn cluster_info(&self) -> Result<&ClusterInfo> {
+        if let Some(info) = self.cluster.get() {
+            return Ok(info);
+        }
+        let info = ClusterInfo {
+            rpc_url: self.rpc.url(),
+            ws_url: self.ws_url.clone(),
+            genesis_hash: self.rpc.get_genesis_hash()?,
+            solana_core: self.rpc.get_version()?.solana_core,
+        };
+        Ok(self.cluster.get_or_init(|| info))
+    }
+
+    /// Адрес PDA аккаунта пользователя.
+    pub fn user_pda(&self, wallet: &Pubkey) -> Pubkey {
+        find_user_pda(&self.program_id, wallet).0
+    }
+
+    /// Загружает сырые данные аккаунта (через кэш, если он включён).
+    pub fn get_account(&self, key: &Pubkey) -> Result<Option<Account>> {
+        get_account_cached(&self.rpc, self.cache(), key)
+    }
+
+    /// Загружает аккаунт программы и декодирует его в `T`.
+    /// Проверяет, что аккаунт существует и принадлежит программе Verta.
+    pub fn get_program_account<T: VertaAccount>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .get_account(address)?
+            .ok_or(Error::AccountNotFound(*address))?;
+        if account.owner != self.program_id {
+            return Err(Error::InvalidOwner {
+                account: *address,
+                owner: account.owner,
+            });
+        }
+        T::try_from_account_data(&account.data).map_err(|source| Error::Decode {
+            account: *address,
+            source,
+        })
+    }
+
+    /// Загружает аккаунт пользователя по адресу его кошелька (PDA выводится автоматически).
+    pub fn get_user(&self, wallet: &Pubkey) -> Result<UserAccount> {
+        self.get_program_account(&self.user_pda(wallet))
+    }
+
+    /// Загружает запись о подтверждённом вкладе.
+    pub fn get_contribution(&self, contributor: &Pubkey, contribution_id: u64) -> Result<ContributionAccount> {
+        let address = find_contribution_pda(&self.program_id, contributor, contribution_id).0;
+        self.get_program_account(&address)
+    }
+
+    /// Загружает глобальную конфигурацию программы.
+    pub fn get_config(&self) -> Result<ConfigAccount> {
+        self.get_program_account(&find_config_pda(&self.program_id).0)
+    }
+
+    /// Загружает учёт начислений верификатора (существует после его первого начисления).
+    pub fn get_verifier(&self, verifier: &Pubkey) -> Result<VerifierAccount> {
+        self.get_program_account(&find_verifier_pda(&self.program_id, verifier).0)
+    }
+
+    /// Загружает запись журнала модерации с номером `index`.
+    pub fn get_moderation_log(&self, index: u64) -> Result<ModerationLogAccount> {
+        self.get_program_account(&find_moderation_log_pda(&self.program_id, index).0)
+    }
+
+    /// Загружает казну программы (существует после первой комиссии, зачисленной в казну).
+    pub fn get_treasury(&self) -> Result<TreasuryAccount> {
+        self.get_program_account(&find_treasury_pda(&self.program_id).0)
+    }
+
+    /// Загружает глобальную статистику (существует после первой регистрации).
+    pub fn get_global_stats(&self) -> Result<GlobalStatsAccount> {
+        self.get_program_account(&find_global_stats_pda(&self.program_id).0)
+    }
+
+    /// Загружает спор по вкладу `contribution_id` автора `contributor`.
+    pub fn get_challenge(&self, contributor: &Pubkey, contribution_id: u64) -> Result<ChallengeAccount> {
+        let (contribution_pda, _) = find_contribution_pda(&self.program_id, contributor, contribution_id);
+        self.get_program_account(&find_challenge_pda(&self.program_id, &contribution_pda).0)
+    }
+
+    /// Загружает таблицу кодов причин начисления (существует после первого SetReasonCodes).
+    pub fn get_reason_codes(&self) -> Result<ReasonCodeTableAccount> {
+        self.get_program_account(&find_reason_codes_pda(&self.program_id).0)
+    }
+
+    /// Загружает индекс шарда `shard` (существует после первой регистрации в шарде).
+    pub fn get_shard_index(&self, shard: u8) -> Result<ShardIndexAccount> {
+        self.get_program_account(&find_shard_index_pda(&self.program_id, shard).0)
+    }
+
+    /// Подписывает, отправляет и подтверждает транзакцию из инструкций
+    /// с параметрами подтверждения клиента.
+    pub fn send_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        self.send_instructions_with_config(instructions, payer, signers, &self.confirmation)
+    }
+
+    /// Подписывает, отправляет и подтверждает транзакцию из инструкций,
+    /// после чего инвалидирует в кэше все изменённые аккаунты.
+    pub fn send_instructions_with_config(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+        confirmation: &ConfirmationConfig,
+    ) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let mut all_signers: Vec<&Keypair> = vec![payer];
+        all_signers.extend(signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+        let mut with_budget = Vec::with_capacity(instructions.len() + 1);
+        if let Some(bytes) = self.heap_frame {
+            with_budget.push(ComputeBudgetInstruction::request_heap_frame(bytes));
+        }
+        with_budget.extend_from_slice(instructions);
+        let transaction = Transaction::new_signed_with_payer(
+            &with_budget,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let result = send_and_confirm(&self.rpc, &self.ws_url, &transaction, confirmation);
+        if let Some(cache) = self.cache() {
+            cache.invalidate_writes(instructions);
+        }
+        result
+    }
+
+    pub fn register_user(&self, user: &Keypair) -> Result<Signature> {
+        let ix = instruction::register_user(&self.program_id, &user.pubkey());
+        self.send_instructions(&[ix], user, &[])
+    }
+
+    /// Регистрирует пользователя за счёт спонсора: `sponsor` платит комиссию, ренту
+    /// и плату за регистрацию, `user` только подписывает.
+    pub fn register_user_sponsored(&self, user: &Keypair, sponsor: &Keypair) -> Result<Signature> {
+        let ix = instruction::with_payer(instruction::register_user(&self.program_id, &user.pubkey()), &sponsor.pubkey());
+        self.send_instructions(&[ix], sponsor, &[user])
+    }
+
+    /// Начисляет карму пользователю; `verifier` должен быть авторизованным верификатором.
+    /// `reason` — код из таблицы причин или `REASON_NONE`.
+    pub fn add_karma(
+        &self,
+        wallet: &Pubkey,
+        amount: u64,
+        category: u8,
+        reason: u16,
+        verifier: &Keypair,
+    ) -> Result<Signature> {
+        let ix = instruction::add_karma(&self.program_id, &verifier.pubkey(), wallet, amount, category, reason);
+        self.send_instructions(&[ix], verifier, &[])
+    }
+
+    /// Пересчитывает уровень; если в конфигурации включены награды, токены за уровень,
+    /// который ещё не награждался, минтятся на ATA пользователя, а на уровнях из badge_levels
+    /// выдаётся бейдж. Метаданные NFT-бейджа (если он выпущен) переключаются на новый уровень.
+    /// Недостающие ATA создаются за счёт `payer`.
+    pub fn update_level(&self, wallet: &Pubkey, payer: &Keypair) -> Result<Signature> {
+        let params = self.get_config()?.params;
+        let user = self.get_user(wallet)?;
+        // Уровень растёт не больше чем на 1: награда и бейдж зависят только от следующего уровня
+        let next_level = user.level.saturating_add(1);
+        let mut instructions = Vec::new();
+        let mut ix = if params.level_rewards_enabled() && next_level > user.rewarded_level {
+            instructions.push(create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                wallet,
+                &params.reward_mint,
+                &spl_token::id(),
+            ));
+            instruction::update_level_with_reward(&self.program_id, wallet, &params.reward_mint)
+        } else {
+            instruction::update_level(&self.program_id, wallet)
+        };
+        if params.is_badge_level(next_level) && !user.has_badge(next_level) {
+            let (badge_mint, _) = find_badge_mint_pda(&self.program_id, next_level);
+            instructions.push(create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                wallet,
+                &badge_mint,
+                &spl_token_2022::id(),
+            ));
+            instruction::add_level_badge_accounts(&mut ix, &self.program_id, wallet, next_level);
+        }
+        // Метаданные NFT-бейджа обновляются, только если пользователь его выпустил
+        if params.badge_nft_enabled() {
+            let (badge_nft_mint, _) = find_badge_nft_mint_pda(&self.program_id, wallet);
+            if self.get_account(&badge_nft_mint)?.is_some() {
+                instruction::add_badge_nft_accounts(&mut ix, &self.program_id, wallet);
+            }
+        }
+        instructions.push(ix);
+        self.send_instructions(&instructions, payer, &[])
+    }
+
+    /// Подтверждает вклад пользователя и начисляет за него карму.
+    pub fn verify_contribution(
+        &self,
+        verifier: &Keypair,
+        contributor: &Pubkey,
+        contribution_id: u64,
+        karma_awarded: u64,
+    ) -> Result<Signature> {
+        let ix = instruction::verify_contribution(
+            &self.program_id,
+            &verifier.pubkey(),
+            contributor,
+            contribution_id,
+            karma_awarded,
+        );
+        self.send_instructions(&[ix], verifier, &[])
+    }
+
+    /// Пополняет баланс PDA пользователя лампортами `funder`.
+    pub fn top_up_account(&self, wallet: &Pubkey, lamports: u64, funder: &Keypair) -> Result<Signature> {
+        let ix = instruction::top_up_account(&self.program_id, &funder.pubkey(), wallet, lamports);
+        self.send_instructions(&[ix], funder, &[])
+    }
+
+    /// Привязывает солёный хеш внешней идентичности к аккаунту пользователя.
+    pub fn bind_external_identity(&self, user: &Keypair, platform_id_hash: [u8; 32]) -> Result<Signature> {
+        let ix = instruction::bind_external_identity(&self.program_id, &user.pubkey(), platform_id_hash);
+        self.send_instructions(&[ix], user, &[])
+    }
+
+    /// Создаёт глобальную конфигурацию; `authority` — upgrade authority программы.
+    pub fn initialize_config(&self, authority: &Keypair, admin: &Pubkey, params: ConfigParams) -> Result<Signature> {
+        let ix = instruction::initialize_config(&self.program_id, &authority.pubkey(), admin, params);
+        self.send_instructions(&[ix], authority, &[])
+    }
+
+    /// Переводит карму отправителя другому пользователю (за вычетом комиссии).
+    pub fn transfer_karma(&self, sender: &Keypair, recipient: &Pubkey, amount: u64) -> Result<Signature> {
+        let ix = instruction::transfer_karma(&self.program_id, &sender.pubkey(), recipient, amount);
+        self.send_instructions(&[ix], sender, &[])
+    }
+
+    /// Применяет затухание кармы пользователя; `payer` может быть любым аккаунтом (keeper).
+    pub fn decay_karma(&self, wallet: &Pubkey, payer: &Keypair) -> Result<Signature> {
+        let ix = instruction::decay_karma(&self.program_id, wallet);
+        self.send_instructions(&[ix], payer, &[])
+    }
+
+    /// Обновляет аккаунт пользователя `wallet` до текущей схемы; недостающую ренту вносит `payer`.
+    pub fn migrate_account(&self, wallet: &Pubkey, payer: &Keypair) -> Result<Signature> {
+        let ix = instruction::migrate_account(&self.program_id, &payer.pubkey(), wallet);
+        self.send_instructions(&[ix], payer, &[])
+    }
+
+    /// Обновляет глобальную конфигурацию до текущей схемы; недостающую ренту вносит `payer`.
+    pub fn migrate_config(&self, payer: &Keypair) -> Result<Signature> {
+        let ix = instruction::migrate_config(&self.program_id, &payer.pubkey());
+        self.send_instructions(&[ix], payer, &[])
+    }
+
+    /// Выводит `lamports` из казны на `destination`; `admin` — администратор из конфигурации.
+    pub fn withdraw_treasury(&self, admin: &Keypair, destination: &Pubkey, lamports: u64) -> Result<Signature> {
+        let ix = instruction::withdraw_treasury(&self.program_id, &admin.pubkey(), destination, lamports);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Поручается за пользователя `endorsee` с весом `weight`.
+    pub fn endorse(&self, endorser: &Keypair, endorsee: &Pubkey, weight: u64) -> Result<Signature> {
+        let ix = instruction::endorse(&self.program_id, &endorser.pubkey(), endorsee, weight);
+        self.send_instructions(&[ix], endorser, &[])
+    }
+
+    /// Поручительство, запись которого оплачивает `sponsor`.
+    pub fn endorse_sponsored(&self, endorser: &Keypair, endorsee: &Pubkey, weight: u64, sponsor: &Keypair) -> Result<Signature> {
+        let ix = instruction::with_payer(
+            instruction::endorse(&self.program_id, &endorser.pubkey(), endorsee, weight),
+            &sponsor.pubkey(),
+        );
+        self.send_instructions(&[ix], sponsor, &[endorser])
+    }
+
+    /// Закрывает аккаунт пользователя; рента возвращается тому, кто вносил её при регистрации
+    /// (пользователю или спонсору).
+    pub fn close_user_account(&self, user: &Keypair) -> Result<Signature> {
+        let rent_payer = self.get_user(&user.pubkey())?.rent_payer;
+        let ix = instruction::close_user_account(&self.program_id, &user.pubkey(), &rent_payer);
+        self.send_instructions(&[ix], user, &[])
+    }
+
+    /// Списывает карму пользователя; `moderator` — администратор из конфигурации.
+    /// Действие записывается в журнал модерации со следующим свободным номером.
+    pub fn slash_karma(
+        &self,
+        wallet: &Pubkey,
+        amount: u64,
+        reason_code: u16,
+        reason_hash: [u8; 32],
+        moderator: &Keypair,
+    ) -> Result<Signature> {
+        let log_index = self.get_config()?.moderation_log_len;
+        let ix = instruction::slash_karma(
+            &self.program_id,
+            &moderator.pubkey(),
+            wallet,
+            amount,
+            reason_code,
+            reason_hash,
+            log_index,
+        );
+        self.send_instructions(&[ix], moderator, &[])
+    }
+
+    /// Замораживает аккаунт пользователя; действие записывается в журнал модерации.
+    pub fn freeze_user(
+        &self,
+        wallet: &Pubkey,
+        reason_code: u16,
+        reason_hash: [u8; 32],
+        moderator: &Keypair,
+    ) -> Result<Signature> {
+        let log_index = self.get_config()?.moderation_log_len;
+        let ix = instruction::freeze_user(&self.program_id, &moderator.pubkey(), wallet, reason_code, reason_hash, log_index);
+        self.send_instructions(&[ix], moderator, &[])
+    }
+
+    /// Размораживает аккаунт пользователя; действие записывается в журнал модерации.
+    pub fn unfreeze_user(&self, wallet: &Pubkey, moderator: &Keypair) -> Result<Signature> {
+        let log_index = self.get_config()?.moderation_log_len;
+        let ix = instruction::unfreeze_user(&self.program_id, &moderator.pubkey(), wallet, log_index);
+        self.send_instructions(&[ix], moderator, &[])
+    }
+
+    /// Выносит пользователю предупреждение; на пороге из конфигурации аккаунт замораживается.
+    /// Действие записывается в журнал модерации.
+    pub fn issue_strike(
+        &self,
+        wallet: &Pubkey,
+        reason_code: u16,
+        reason_hash: [u8; 32],
+        moderator: &Keypair,
+    ) -> Result<Signature> {
+        let log_index = self.get_config()?.moderation_log_len;
+        let ix = instruction::issue_strike(&self.program_id, &moderator.pubkey(), wallet, reason_code, reason_hash, log_index);
+        self.send_instructions(&[ix], moderator, &[])
+    }
+
+    /// Изменяет параметры конфигурации; `admin` — администратор из конфигурации.
+    pub fn update_config(&self, admin: &Keypair, params: ConfigParams) -> Result<Signature> {
+        let ix = instruction::update_config(&self.program_id, &admin.pubkey(), params);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Предлагает нового администратора (`None` отменяет предложение).
+    pub fn propose_authority(&self, admin: &Keypair, new_authority: Option<Pubkey>) -> Result<Signature> {
+        let ix = instruction::propose_authority(&self.program_id, &admin.pubkey(), new_authority);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Выпускает NFT-бейдж Metaplex пользователю `user` (он же платит ренту).
+    pub fn mint_badge_nft(&self, user: &Keypair) -> Result<Signature> {
+        let ix = instruction::mint_badge_nft(&self.program_id, &user.pubkey());
+        self.send_instructions(&[ix], user, &[])
+    }
+
+    /// Выпускает NFT-бейдж пользователю `user` за счёт спонсора.
+    pub fn mint_badge_nft_sponsored(&self, user: &Keypair, sponsor: &Keypair) -> Result<Signature> {
+        let ix = instruction::with_payer(instruction::mint_badge_nft(&self.program_id, &user.pubkey()), &sponsor.pubkey());
+        self.send_instructions(&[ix], sponsor, &[user])
+    }
+
+    /// Создаёт непередаваемый mint бейджа за уровень `level`.
+    pub fn initialize_badge_mint(&self, admin: &Keypair, level: u8) -> Result<Signature> {
+        let ix = instruction::initialize_badge_mint(&self.program_id, &admin.pubkey(), level);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Оспаривает вклад; `challenger` вносит залог из конфигурации.
+    pub fn challenge_contribution(&self, challenger: &Keypair, contributor: &Pubkey, contribution_id: u64) -> Result<Signature> {
+        let ix = instruction::challenge_contribution(&self.program_id, &challenger.pubkey(), contributor, contribution_id);
+        self.send_instructions(&[ix], challenger, &[])
+    }
+
+    /// Разрешает спор по вкладу: `upheld` = true отзывает карму, false возвращает её автору.
+    pub fn resolve_challenge(
+        &self,
+        admin: &Keypair,
+        contributor: &Pubkey,
+        contribution_id: u64,
+        upheld: bool,
+    ) -> Result<Signature> {
+        let challenger = self.get_challenge(contributor, contribution_id)?.challenger;
+        let ix = instruction::resolve_challenge(
+            &self.program_id,
+            &admin.pubkey(),
+            contributor,
+            contribution_id,
+            &challenger,
+            upheld,
+        );
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Регистрирует верификатора с весом `weight` (базисные пункты) или меняет его вес.
+    pub fn add_verifier(&self, admin: &Keypair, verifier: &Pubkey, weight: u16) -> Result<Signature> {
+        let ix = instruction::add_verifier(&self.program_id, &admin.pubkey(), verifier, weight);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Снимает регистрацию верификатора.
+    pub fn remove_verifier(&self, admin: &Keypair, verifier: &Pubkey) -> Result<Signature> {
+        let ix = instruction::remove_verifier(&self.program_id, &admin.pubkey(), verifier);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Заменяет таблицу кодов причин начисления (пустые слоты — `REASON_NONE`).
+    pub fn set_reason_codes(&self, admin: &Keypair, codes: [u16; MAX_REASON_CODES]) -> Result<Signature> {
+        let ix = instruction::set_reason_codes(&self.program_id, &admin.pubkey(), codes);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Включает или снимает аварийную паузу программы.
+    pub fn set_paused(&self, admin: &Keypair, paused: bool) -> Result<Signature> {
+        let ix = instruction::set_paused(&self.program_id, &admin.pubkey(), paused);
+        self.send_instructions(&[ix], admin, &[])
+    }
+
+    /// Принимает права администратора, предложенные ключу `new_admin`.
+    pub fn accept_authority(&self, new_admin: &Keypair) -> Result<Signature> {
+        let ix = instruction::accept_authority(&self.program_id, &new_admin.pubkey());
+        self.send_instructions(&[ix], new_admin, &[])
+    }
+}
+
+// http(s)://host:8899 -> ws(s)://host:8900, как это делает solana-cli
+fn ws_url_from_rpc_url(rpc_url: &str) -> String {
+    let ws_url = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    };
+    ws_url.replacen(":8899", ":8900", 1)
+}
@@ -0,0 +1,152 @@
+// Настраиваемое подтверждение транзакций.
+// `RpcClient::send_and_confirm_transaction` использует уровень подтверждения клиента и
+// может ждать очень долго; здесь уровень, таймаут, интервал опроса и способ ожидания
+// (опрос RPC или websocket-подписка) задаются явно.
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_config::{RpcSendTransactionConfig, RpcSignatureSubscribeConfig},
+    rpc_response::{ProcessedSignatureResult, RpcSignatureResult},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature, transaction::Transaction};
+
+use super::error::{Error, Result};
+
+/// Способ ожидания подтверждения.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ConfirmationMethod {
+    /// Периодически опрашивать статус подписи через RPC
+    #[default]
+    Polling,
+    /// Подписаться на статус подписи через websocket
+    Websocket,
+}
+
+/// Параметры подтверждения отправленной транзакции.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfirmationConfig {
+    pub commitment: CommitmentConfig,
+    /// Максимальное время ожидания подтверждения
+    pub timeout: Duration,
+    /// Интервал между запросами статуса (только для `Polling`)
+    pub poll_interval: Duration,
+    pub method: ConfirmationMethod,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentConfig::confirmed(),
+            timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_millis(500),
+            method: ConfirmationMethod::Polling,
+        }
+    }
+}
+
+impl ConfirmationConfig {
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn with_method(mut self, method: ConfirmationMethod) -> Self {
+        self.method = method;
+        self
+    }
+}
+
+/// Отправляет подписанную транзакцию и ждёт её подтверждения согласно `config`.
+pub fn send_and_confirm(
+    rpc: &RpcClient,
+    ws_url: &str,
+    transaction: &Transaction,
+    config: &ConfirmationConfig,
+) -> Result<Signature> {
+    let signature = rpc.send_transaction_with_config(
+        transaction,
+        RpcSendTransactionConfig {
+            preflight_commitment: Some(config.commitment.commitment),
+            ..RpcSendTransactionConfig::default()
+        },
+    )?;
+
+    match config.method {
+        ConfirmationMethod::Polling => confirm_by_polling(rpc, transaction, &signature, config)?,
+        ConfirmationMethod::Websocket => confirm_by_websocket(ws_url, &signature, config)?,
+    }
+    Ok(signature)
+}
+
+fn confirm_by_polling(
+    rpc: &RpcClient,
+    transaction: &Transaction,
+    signature: &Signature,
+    config: &ConfirmationConfig,
+) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = rpc.get_signature_status_with_commitment(signature, config.commitment)? {
+            return status.map_err(Error::Transaction);
+        }
+        // Если blockhash истёк, транзакция уже никогда не попадёт в блок. Но она могла попасть
+        // в блок до этого и ещё не достичь нужного уровня подтверждения: тогда BlockhashExpired
+        // привёл бы к повторной отправке уже выполненной транзакции, поэтому ждём дальше.
+        if !rpc.is_blockhash_valid(&transaction.message.recent_blockhash, config.commitment)? {
+            let processed =
+                rpc.get_signature_status_with_commitment_and_history(signature, CommitmentConfig::processed(), true)?;
+            if processed.is_none() {
+                return Err(Error::BlockhashExpired(*signature));
+            }
+        }
+        if started.elapsed() >= config.timeout {
+            return Err(Error::ConfirmationTimeout(*signature));
+        }
+        thread::sleep(config.poll_interval);
+    }
+}
+
+fn confirm_by_websocket(
+    ws_url: &str,
+    signature: &Signature,
+    config: &ConfirmationConfig,
+) -> Result<()> {
+    let (_subscription, receiver) = PubsubClient::signature_subscribe(
+        ws_url,
+        signature,
+        Some(RpcSignatureSubscribeConfig {
+            commitment: Some(config.commitment),
+            enable_received_notification: Some(false),
+        }),
+    )?;
+
+    let started = Instant::now();
+    loop {
+        let remaining = config.timeout.saturating_sub(started.elapsed());
+        let response = receiver
+            .recv_timeout(remaining)
+            .map_err(|_| Error::ConfirmationTimeout(*signature))?;
+        if let RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult { err }) = response.value {
+            return match err {
+                Some(err) => Err(Error::Transaction(err)),
+                None => Ok(()),
+            };
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Идемпотентная отправка транзакций с ключом дедупликации.
+// Ключ записывается в транзакцию memo-инструкцией. Перед отправкой среди недавних
+// подписей плательщика ищется успешная транзакция с тем же ключом: если она есть,
+// повторная отправка не выполняется. Так повторный запуск пакетного скрипта после
+// неоднозначного таймаута не начислит карму дважды.
+
+use std::str::FromStr;
+
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+};
+
+use super::{client::VertaClient, error::Result};
+
+/// ID программы SPL Memo (v2).
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Префикс, отличающий ключи дедупликации Verta от прочих memo.
+pub const DEDUPE_MEMO_PREFIX: &str = "verta-dedupe:";
+
+/// Сколько последних подписей плательщика просматривать при поиске ключа.
+pub const DEDUPE_SIGNATURE_LOOKBACK: usize = 1000;
+
+/// Memo-инструкция с ключом дедупликации.
+pub fn dedupe_memo_instruction(dedupe_key: &str) -> Instruction {
+    let memo = format!("{}{}", DEDUPE_MEMO_PREFIX, dedupe_key);
+    Instruction::new_with_bytes(MEMO_PROGRAM_ID, memo.as_bytes(), vec![])
+}
+
+impl VertaClient {
+    /// Ищет среди недавних транзакций плательщика успешную транзакцию с ключом `dedupe_key`.
+    pub fn find_deduplicated(&self, payer: &Pubkey, dedupe_key: &str) -> Result<Option<Signature>> {
+        let memo = format!("{}{}", DEDUPE_MEMO_PREFIX, dedupe_key);
+        let statuses = self.rpc().get_signatures_for_address_with_config(
+            payer,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(DEDUPE_SIGNATURE_LOOKBACK),
+                commitment: Some(self.commitment()),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )?;
+        // RPC возвращает memo в виде "[длина] текст", несколько memo разделены "; "
+        let found = statuses.into_iter().find(|status| {
+            status.err.is_none()
+                && status
+                    .memo
+                    .as_deref()
+                    .is_some_and(|memos| memos.split("; ").any(|m| m.ends_with(&memo)))
+        });
+        Ok(found.and_then(|status| Signature::from_str(&status.signature).ok()))
+    }
+
+    /// Отправляет инструкции, только если транзакция с этим ключом ещё не выполнялась.
+    /// Возвращает подпись новой или ранее выполненной транзакции.
+    pub fn send_instructions_idempotent(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+        dedupe_key: &str,
+    ) -> Result<Signature> {
+        if let Some(signature) = self.find_deduplicated(&payer.pubkey(), dedupe_key)? {
+            return Ok(signature);
+        }
+        let mut with_memo = instructions.to_vec();
+        with_memo.push(dedupe_memo_instruction(dedupe_key));
+        self.send_instructions(&with_memo, payer, signers)
+    }
+}
@@ -0,0 +1,106 @@
+// Ошибки клиентского SDK
+
+use std::fmt;
+
+use solana_client::{client_error::ClientError, nonblocking::pubsub_client::PubsubClientError};
+use solana_sdk::{
+    instruction::InstructionError, pubkey::Pubkey, signature::Signature, transaction::TransactionError,
+};
+
+use crate::VertaError;
+
+/// Ошибка операций SDK.
+#[derive(Debug)]
+pub enum Error {
+    /// Ошибка RPC-запроса
+    Rpc(Box<ClientError>),
+    /// Ошибка websocket-подписки
+    Pubsub(Box<PubsubClientError>),
+    /// Аккаунт не существует
+    AccountNotFound(Pubkey),
+    /// Аккаунт принадлежит другой программе
+    InvalidOwner { account: Pubkey, owner: Pubkey },
+    /// Данные аккаунта не удалось десериализовать
+    Decode { account: Pubkey, source: std::io::Error },
+    /// Транзакция попала в блок, но завершилась ошибкой
+    Transaction(TransactionError),
+    /// Подтверждение не получено за отведённое время
+    ConfirmationTimeout(Signature),
+    /// Blockhash транзакции истёк, а сама она не попала в блок даже на уровне processed:
+    /// её можно безопасно переподписать и отправить снова
+    BlockhashExpired(Signature),
+    /// Симуляция завершилась без return data от программы
+    MissingReturnData,
+    /// Сценарий тестовых данных некорректен
+    InvalidScenario(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Rpc(err) => write!(f, "RPC error: {}", err),
+            Error::Pubsub(err) => write!(f, "pubsub error: {}", err),
+            Error::AccountNotFound(account) => write!(f, "account {} not found", account),
+            Error::InvalidOwner { account, owner } => {
+                write!(f, "account {} is owned by unexpected program {}", account, owner)
+            }
+            Error::Decode { account, source } => {
+                write!(f, "failed to decode account {}: {}", account, source)
+            }
+            Error::Transaction(err) => write!(f, "transaction failed: {}", err),
+            Error::ConfirmationTimeout(signature) => {
+                write!(f, "transaction {} was not confirmed in time", signature)
+            }
+            Error::BlockhashExpired(signature) => {
+                write!(f, "blockhash of transaction {} expired before confirmation", signature)
+            }
+            Error::MissingReturnData => write!(f, "program returned no data"),
+            Error::InvalidScenario(reason) => write!(f, "invalid fixture scenario: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(err: ClientError) -> Self {
+        Error::Rpc(Box::new(err))
+    }
+}
+
+impl From<PubsubClientError> for Error {
+    fn from(err: PubsubClientError) -> Self {
+        Error::Pubsub(Box::new(err))
+    }
+}
+
+/// Результат операций SDK.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Декодирует ошибку программы Verta из ошибки транзакции.
+/// Код `Custom` интерпретируется как `VertaError`, поэтому результат имеет смысл,
+/// только если упавшая инструкция принадлежит программе Verta.
+pub fn decode_custom_error(error: &TransactionError) -> Option<VertaError> {
+    match error {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => VertaError::from_code(*code),
+        _ => None,
+    }
+}
+
+impl Error {
+    /// Ошибка программы Verta, если транзакция (или её симуляция перед отправкой) упала с ней.
+    pub fn verta_error(&self) -> Option<VertaError> {
+        match self {
+            Error::Transaction(err) => decode_custom_error(err),
+            Error::Rpc(err) => err.get_transaction_error().as_ref().and_then(decode_custom_error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Декодирование событий программы Verta из логов транзакций.
+// Событие — строка "Program data: <base64>" в логе. Такие строки пишет любая программа,
+// поэтому по строкам "invoke"/"success"/"failed" отслеживается, какая программа
+// выполняется, и декодируются только данные, записанные программой Verta.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+
+use super::{client::VertaClient, error::Result};
+use crate::event::VertaEvent;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Извлекает события программы `program_id` из логов транзакции в порядке записи.
+pub fn parse_events_from_logs(program_id: &Pubkey, logs: &[String]) -> Vec<VertaEvent> {
+    let program_id = program_id.to_string();
+    let mut call_stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if call_stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            // Несколько срезов sol_log_data записываются через пробел
+            let bytes: Option<Vec<u8>> = data
+                .split(' ')
+                .map(|chunk| STANDARD.decode(chunk).ok())
+                .collect::<Option<Vec<_>>>()
+                .map(|chunks| chunks.concat());
+            if let Some(event) = bytes.as_deref().and_then(VertaEvent::decode) {
+                events.push(event);
+            }
+            continue;
+        }
+
+        let mut words = line.split(' ');
+        if let (Some("Program"), Some(id), Some(status)) = (words.next(), words.next(), words.next()) {
+            if status == "invoke" {
+                call_stack.push(id);
+            } else if status == "success" || status == "failed:" {
+                call_stack.pop();
+            }
+        }
+    }
+    events
+}
+
+impl VertaClient {
+    /// Загружает подтверждённую транзакцию и извлекает из её логов события Verta.
+    pub fn parse_events_from_transaction(&self, signature: &Signature) -> Result<Vec<VertaEvent>> {
+        let transaction = self.rpc().get_transaction(signature, UiTransactionEncoding::Json)?;
+        let logs: Option<Vec<String>> = transaction.transaction.meta.and_then(|meta| meta.log_messages.into());
+        Ok(parse_events_from_logs(self.program_id(), &logs.unwrap_or_default()))
+    }
+}
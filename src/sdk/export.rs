@@ -0,0 +1,106 @@
+// Экспорт неподписанных транзакций для подписи вне SDK.
+// Веб-кошельки (wallet adapter) и мультисиг-интерфейсы принимают транзакцию в wire-формате.
+// Вместе с ней выгружается JSON-описание аккаунтов и назначения, чтобы подписант
+// мог проверить, что именно он подписывает, не разбирая байты инструкций.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshDeserialize;
+use serde::Serialize;
+use solana_sdk::{instruction::Instruction, message::Message, pubkey::Pubkey, transaction::Transaction};
+
+use super::{client::VertaClient, error::Result};
+use crate::VertaInstruction;
+
+/// Неподписанная транзакция с описанием для внешнего подписанта.
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionPayload {
+    /// Назначение транзакции в свободной форме
+    pub intent: String,
+    /// Транзакция в wire-формате (base64) с пустыми подписями
+    pub transaction: String,
+    /// Сериализованное сообщение (base64) — то, что подписывается
+    pub message: String,
+    pub fee_payer: String,
+    pub recent_blockhash: String,
+    /// Аккаунты, которые должны подписать транзакцию
+    pub signers: Vec<String>,
+    pub instructions: Vec<InstructionDescription>,
+}
+
+/// Описание одной инструкции транзакции.
+#[derive(Clone, Debug, Serialize)]
+pub struct InstructionDescription {
+    pub program_id: String,
+    /// Декодированная инструкция Verta (None для инструкций других программ)
+    pub decoded: Option<String>,
+    /// Данные инструкции (base64)
+    pub data: String,
+    pub accounts: Vec<AccountDescription>,
+}
+
+/// Аккаунт инструкции и его права.
+#[derive(Clone, Debug, Serialize)]
+pub struct AccountDescription {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl TransactionPayload {
+    /// Описание в формате JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("serializing a payload into JSON never fails")
+    }
+}
+
+impl VertaClient {
+    /// Собирает неподписанную транзакцию со свежим blockhash для подписи внешним кошельком.
+    pub fn export_transaction(
+        &self,
+        instructions: &[Instruction],
+        fee_payer: &Pubkey,
+        intent: &str,
+    ) -> Result<TransactionPayload> {
+        let blockhash = self.rpc().get_latest_blockhash()?;
+        let message = Message::new_with_blockhash(instructions, Some(fee_payer), &blockhash);
+        let signers = message.account_keys[..message.header.num_required_signatures as usize]
+            .iter()
+            .map(Pubkey::to_string)
+            .collect();
+        let message_bytes = message.serialize();
+        let transaction = Transaction::new_unsigned(message);
+        let transaction_bytes =
+            bincode::serialize(&transaction).expect("serializing a transaction into a Vec never fails");
+
+        Ok(TransactionPayload {
+            intent: intent.to_string(),
+            transaction: STANDARD.encode(transaction_bytes),
+            message: STANDARD.encode(message_bytes),
+            fee_payer: fee_payer.to_string(),
+            recent_blockhash: blockhash.to_string(),
+            signers,
+            instructions: instructions.iter().map(|ix| self.describe_instruction(ix)).collect(),
+        })
+    }
+
+    fn describe_instruction(&self, instruction: &Instruction) -> InstructionDescription {
+        let decoded = (instruction.program_id == *self.program_id())
+            .then(|| VertaInstruction::try_from_slice(&instruction.data).ok())
+            .flatten()
+            .map(|decoded| format!("{:?}", decoded));
+        InstructionDescription {
+            program_id: instruction.program_id.to_string(),
+            decoded,
+            data: STANDARD.encode(&instruction.data),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| AccountDescription {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Воспроизводимые тестовые данные для локальной сети.
+// Сценарий описывает пользователей, их карму по категориям и подтверждённые вклады.
+// Ключи пользователей выводятся из сида сценария и имени, поэтому на чистом localnet
+// один и тот же сценарий всегда даёт одни и те же адреса и одинаковое состояние.
+
+use serde::Deserialize;
+use solana_sdk::{
+    hash::hashv,
+    pubkey::Pubkey,
+    signature::{keypair_from_seed, Keypair, Signer},
+};
+
+use super::{client::VertaClient, error::Error, error::Result};
+use crate::{ConfigParams, KARMA_CATEGORY_CODE, KARMA_CATEGORY_COMMUNITY, KARMA_CATEGORY_DOCS, KARMA_CATEGORY_REVIEW, REASON_NONE};
+
+/// Лампорты, которые получает каждый пользователь сценария по умолчанию
+pub const DEFAULT_AIRDROP_LAMPORTS: u64 = 1_000_000_000;
+
+/// Сценарий тестовых данных (обычно читается из TOML).
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    pub seed: String, // Сид для вывода ключей пользователей
+    #[serde(default)]
+    pub airdrop_lamports: Option<u64>, // Лампорты на кошелёк пользователя (по умолчанию DEFAULT_AIRDROP_LAMPORTS)
+    #[serde(default)]
+    pub config: ScenarioConfig,
+    #[serde(default)]
+    pub users: Vec<ScenarioUser>,
+    #[serde(default)]
+    pub contributions: Vec<ScenarioContribution>,
+}
+
+/// Отличия конфигурации сценария от ConfigParams::default().
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScenarioConfig {
+    pub max_karma_per_grant: Option<u64>,
+    pub transfer_fee_bps: Option<u16>,
+    pub karma_half_life: Option<u64>,
+    pub verifier_epoch_cap: Option<u64>,
+}
+
+impl ScenarioConfig {
+    /// Параметры конфигурации программы для сценария.
+    pub fn params(&self) -> ConfigParams {
+        let defaults = ConfigParams::default();
+        ConfigParams {
+            max_karma_per_grant: self.max_karma_per_grant.unwrap_or(defaults.max_karma_per_grant),
+            transfer_fee_bps: self.transfer_fee_bps.unwrap_or(defaults.transfer_fee_bps),
+            karma_half_life: self.karma_half_life.unwrap_or(defaults.karma_half_life),
+            verifier_epoch_cap: self.verifier_epoch_cap.unwrap_or(defaults.verifier_epoch_cap),
+            ..defaults
+        }
+    }
+}
+
+/// Пользователь сценария и начисляемая ему карма по категориям.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioUser {
+    pub name: String,
+    #[serde(default)]
+    pub code: u64,
+    #[serde(default)]
+    pub review: u64,
+    #[serde(default)]
+    pub docs: u64,
+    #[serde(default)]
+    pub community: u64,
+}
+
+/// Подтверждённый вклад пользователя `user` (по имени из `users`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioContribution {
+    pub user: String,
+    pub id: u64,
+    pub karma: u64,
+}
+
+/// Пользователь, созданный по сценарию.
+#[derive(Debug)]
+pub struct FixtureUser {
+    pub name: String,
+    pub keypair: Keypair,
+}
+
+/// Детерминированная пара ключей пользователя `name` сценария с сидом `seed`.
+pub fn fixture_keypair(seed: &str, name: &str) -> Keypair {
+    let seed = hashv(&[seed.as_bytes(), name.as_bytes()]);
+    keypair_from_seed(seed.as_ref()).expect("32-byte seed is always valid")
+}
+
+impl VertaClient {
+    /// Создаёт состояние по сценарию: конфигурацию (администратор — `authority`),
+    /// пользователей с кармой и подтверждённые вклады. Рассчитан на чистый localnet:
+    /// `authority` должен быть upgrade authority программы, кошельки пополняются через airdrop.
+    pub fn apply_scenario(&self, authority: &Keypair, scenario: &Scenario) -> Result<Vec<FixtureUser>> {
+        let params = scenario.config.params();
+        if !params.is_valid() {
+            return Err(Error::InvalidScenario("config parameters are invalid".to_string()));
+        }
+        for contribution in &scenario.contributions {
+            if !scenario.users.iter().any(|user| user.name == contribution.user) {
+                return Err(Error::InvalidScenario(format!(
+                    "contribution {} references unknown user {}",
+                    contribution.id, contribution.user
+                )));
+            }
+        }
+
+        self.initialize_config(authority, &authority.pubkey(), params)?;
+
+        let airdrop_lamports = scenario.airdrop_lamports.unwrap_or(DEFAULT_AIRDROP_LAMPORTS);
+        let mut users = Vec::with_capacity(scenario.users.len());
+        for user in &scenario.users {
+            let keypair = fixture_keypair(&scenario.seed, &user.name);
+            self.airdrop(&keypair.pubkey(), airdrop_lamports)?;
+            self.register_user(&keypair)?;
+            for (category, amount) in [
+                (KARMA_CATEGORY_CODE, user.code),
+                (KARMA_CATEGORY_REVIEW, user.review),
+                (KARMA_CATEGORY_DOCS, user.docs),
+                (KARMA_CATEGORY_COMMUNITY, user.community),
+            ] {
+                self.grant_in_chunks(authority, &keypair.pubkey(), amount, category, params.max_karma_per_grant)?;
+            }
+            users.push(FixtureUser {
+                name: user.name.clone(),
+                keypair,
+            });
+        }
+
+        for contribution in &scenario.contributions {
+            let contributor = fixture_keypair(&scenario.seed, &contribution.user).pubkey();
+            self.verify_contribution(authority, &contributor, contribution.id, contribution.karma)?;
+        }
+        Ok(users)
+    }
+
+    // Запрашивает airdrop и ждёт его подтверждения
+    fn airdrop(&self, wallet: &Pubkey, lamports: u64) -> Result<()> {
+        let signature = self.rpc().request_airdrop(wallet, lamports)?;
+        self.rpc().poll_for_signature(&signature)?;
+        Ok(())
+    }
+
+    // Начисляет карму частями, не превышающими лимит одного начисления
+    fn grant_in_chunks(&self, verifier: &Keypair, wallet: &Pubkey, amount: u64, category: u8, max_grant: u64) -> Result<()> {
+        let mut remaining = amount;
+        while remaining > 0 {
+            let chunk = remaining.min(max_grant);
+            self.add_karma(wallet, chunk, category, REASON_NONE, verifier)?;
+            remaining -= chunk;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+// Определение версии и возможностей задеплоенной программы.
+// Инструкция GetProgramInfo выполняется через симуляцию транзакции, поэтому запрос
+// ничего не стоит и не требует подписи. Ответ читается из return data.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{message::Message, pubkey::Pubkey, transaction::Transaction};
+use solana_transaction_status::UiReturnDataEncoding;
+
+use super::{
+    client::VertaClient,
+    error::{Error, Result},
+    instruction,
+};
+use crate::ProgramInfo;
+
+impl VertaClient {
+    /// Запрашивает версию, версии схем аккаунтов и маску возможностей программы.
+    /// `fee_payer` — любой существующий аккаунт: транзакция только симулируется.
+    pub fn program_info(&self, fee_payer: &Pubkey) -> Result<ProgramInfo> {
+        let ix = instruction::get_program_info(self.program_id());
+        let transaction = Transaction::new_unsigned(Message::new(&[ix], Some(fee_payer)));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = self.rpc().simulate_transaction_with_config(&transaction, config)?.value;
+        if let Some(err) = result.err {
+            return Err(Error::Transaction(err));
+        }
+
+        let return_data = result
+            .return_data
+            .filter(|data| data.program_id == self.program_id().to_string())
+            .ok_or(Error::MissingReturnData)?;
+        let (encoded, UiReturnDataEncoding::Base64) = return_data.data;
+        let decode_error = |source| Error::Decode {
+            account: *self.program_id(),
+            source,
+        };
+        let mut bytes = STANDARD
+            .decode(encoded)
+            .map_err(|err| decode_error(std::io::Error::new(std::io::ErrorKind::InvalidData, err)))?;
+        // Return data обрезается до последнего ненулевого байта, поэтому дополняем нулями до
+        // размера в Borsh. Байты сверх него — поля более новой версии программы, их пропускаем.
+        if bytes.len() < ProgramInfo::LEN {
+            bytes.resize(ProgramInfo::LEN, 0);
+        }
+        borsh::BorshDeserialize::deserialize(&mut bytes.as_slice()).map_err(decode_error)
+    }
+}
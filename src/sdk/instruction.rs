@@ -0,0 +1,633 @@
+// Конструкторы инструкций программы Verta.
+// Порядок аккаунтов должен совпадать с тем, что ожидают обработчики в программе.
+
+use borsh::BorshSerialize;
+use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+use spl_associated_token_account::{get_associated_token_address, get_associated_token_address_with_program_id};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use super::{
+    find_badge_mint_pda, find_badge_nft_mint_pda, find_challenge_pda, find_config_pda, find_contribution_pda, find_endorsement_pda, find_global_stats_pda, find_mint_authority_pda, find_moderation_log_pda, find_program_data_address, find_reason_codes_pda, find_shard_index_pda, find_treasury_pda, find_user_pda, find_verifier_pda,
+};
+use crate::{user_shard, ConfigParams, GrantEnvelope, VertaInstruction, MAX_REASON_CODES};
+
+fn build(program_id: &Pubkey, instruction: &VertaInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    let data = instruction
+        .try_to_vec()
+        .expect("serializing an instruction into a Vec never fails");
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+/// RegisterUser: [user (signer, writable), user_pda (writable), system_program, config_pda,
+/// treasury_pda (writable), stats_pda (writable), shard_index_pda (writable)]
+pub fn register_user(program_id: &Pubkey, user: &Pubkey) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::RegisterUser,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_treasury_pda(program_id).0, false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+            AccountMeta::new(find_shard_index_pda(program_id, user_shard(user)).0, false),
+        ],
+    )
+}
+
+/// Добавляет отдельного плательщика ренты (signer, writable) последним аккаунтом.
+/// Поддерживают RegisterUser, Endorse и MintBadgeNft: так спонсор или релейер
+/// оплачивает создание аккаунтов, а пользователь только подписывает.
+pub fn with_payer(mut ix: Instruction, payer: &Pubkey) -> Instruction {
+    ix.accounts.push(AccountMeta::new(*payer, true));
+    ix
+}
+
+/// AddKarma: [verifier (signer, writable), config_pda, user_pda (writable), verifier_pda (writable), system_program,
+/// stats_pda (writable), reasons_pda, verifier_user_pda]
+///
+/// `reason` — код из таблицы причин или `REASON_NONE`.
+pub fn add_karma(
+    program_id: &Pubkey,
+    verifier: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    category: u8,
+    reason: u16,
+) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::AddKarma { amount, category, reason },
+        vec![
+            AccountMeta::new(*verifier, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new(find_verifier_pda(program_id, verifier).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+            AccountMeta::new_readonly(find_reason_codes_pda(program_id).0, false),
+            AccountMeta::new_readonly(find_user_pda(program_id, verifier).0, false),
+        ],
+    )
+}
+
+/// UpdateLevel: [user_pda (writable), config_pda, stats_pda (writable)]
+pub fn update_level(program_id: &Pubkey, user: &Pubkey) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::UpdateLevel,
+        vec![
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// UpdateLevel с включёнными наградами:
+/// [user_pda (writable), config_pda, stats_pda (writable), reward_mint (writable), user_ata (writable), mint_authority_pda, token_program]
+///
+/// ATA пользователя должен существовать (см. `create_associated_token_account_idempotent`).
+pub fn update_level_with_reward(program_id: &Pubkey, user: &Pubkey, reward_mint: &Pubkey) -> Instruction {
+    let mut ix = update_level(program_id, user);
+    ix.accounts.extend([
+        AccountMeta::new(*reward_mint, false),
+        AccountMeta::new(get_associated_token_address(user, reward_mint), false),
+        AccountMeta::new_readonly(find_mint_authority_pda(program_id).0, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    ix
+}
+
+/// Дополняет UpdateLevel аккаунтами для выдачи бейджа за уровень `level`:
+/// [badge_mint (writable), user_badge_ata (writable), mint_authority_pda, token_2022_program].
+/// Передаются после аккаунтов награды, если она включена.
+pub fn add_level_badge_accounts(ix: &mut Instruction, program_id: &Pubkey, user: &Pubkey, level: u8) {
+    let (badge_mint, _) = find_badge_mint_pda(program_id, level);
+    ix.accounts.extend([
+        AccountMeta::new(badge_mint, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(user, &badge_mint, &spl_token_2022::id()),
+            false,
+        ),
+        AccountMeta::new_readonly(find_mint_authority_pda(program_id).0, false),
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+    ]);
+}
+
+/// BindExternalIdentity: [user (signer), user_pda (writable)]
+pub fn bind_external_identity(program_id: &Pubkey, user: &Pubkey, platform_id_hash: [u8; 32]) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::BindExternalIdentity { platform_id_hash },
+        vec![AccountMeta::new_readonly(*user, true), AccountMeta::new(user_pda, false)],
+    )
+}
+
+/// VerifyContribution: [verifier (signer, writable), config_pda, user_pda (writable), contribution_pda (writable), verifier_pda (writable), system_program,
+/// stats_pda (writable), verifier_user_pda]
+pub fn verify_contribution(
+    program_id: &Pubkey,
+    verifier: &Pubkey,
+    contributor: &Pubkey,
+    contribution_id: u64,
+    karma_awarded: u64,
+) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, contributor);
+    let (contribution_pda, _) = find_contribution_pda(program_id, contributor, contribution_id);
+    build(
+        program_id,
+        &VertaInstruction::VerifyContribution {
+            user_to_verify: *contributor,
+            contribution_id,
+            karma_awarded,
+        },
+        vec![
+            AccountMeta::new(*verifier, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new(contribution_pda, false),
+            AccountMeta::new(find_verifier_pda(program_id, verifier).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+            AccountMeta::new_readonly(find_user_pda(program_id, verifier).0, false),
+        ],
+    )
+}
+
+/// TopUpAccount: [funder (signer, writable), user_pda (writable), system_program]
+pub fn top_up_account(program_id: &Pubkey, funder: &Pubkey, user: &Pubkey, lamports: u64) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::TopUpAccount { lamports },
+        vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// InitializeConfig: [authority (signer, writable), program_data, config_pda (writable), system_program]
+pub fn initialize_config(program_id: &Pubkey, authority: &Pubkey, admin: &Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::InitializeConfig { admin: *admin, params },
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(find_program_data_address(program_id), false),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// UpdateConfig: [admin (signer), config_pda (writable)]
+pub fn update_config(program_id: &Pubkey, admin: &Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::UpdateConfig { params },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// ProposeAuthority: [admin (signer), config_pda (writable)]
+pub fn propose_authority(program_id: &Pubkey, admin: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::ProposeAuthority { new_authority },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// AcceptAuthority: [new_admin (signer), config_pda (writable)]
+pub fn accept_authority(program_id: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::AcceptAuthority,
+        vec![
+            AccountMeta::new_readonly(*new_admin, true),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// SetPaused: [admin (signer), config_pda (writable)]
+pub fn set_paused(program_id: &Pubkey, admin: &Pubkey, paused: bool) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::SetPaused { paused },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// Дополняет UpdateLevel аккаунтами NFT-бейджа пользователя (последними):
+/// [badge_nft_mint, metadata (writable), mint_authority_pda, token_metadata_program].
+pub fn add_badge_nft_accounts(ix: &mut Instruction, program_id: &Pubkey, user: &Pubkey) {
+    let (badge_nft_mint, _) = find_badge_nft_mint_pda(program_id, user);
+    ix.accounts.extend([
+        AccountMeta::new_readonly(badge_nft_mint, false),
+        AccountMeta::new(Metadata::find_pda(&badge_nft_mint).0, false),
+        AccountMeta::new_readonly(find_mint_authority_pda(program_id).0, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+    ]);
+}
+
+/// MintBadgeNft: [user (signer, writable), user_pda, config_pda, badge_nft_mint (writable), user_ata (writable),
+/// metadata (writable), master_edition (writable), mint_authority_pda, token_program,
+/// associated_token_program, token_metadata_program, system_program]
+pub fn mint_badge_nft(program_id: &Pubkey, user: &Pubkey) -> Instruction {
+    let (badge_nft_mint, _) = find_badge_nft_mint_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::MintBadgeNft,
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(find_user_pda(program_id, user).0, false),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(badge_nft_mint, false),
+            AccountMeta::new(get_associated_token_address(user, &badge_nft_mint), false),
+            AccountMeta::new(Metadata::find_pda(&badge_nft_mint).0, false),
+            AccountMeta::new(MasterEdition::find_pda(&badge_nft_mint).0, false),
+            AccountMeta::new_readonly(find_mint_authority_pda(program_id).0, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// InitializeBadgeMint: [admin (signer, writable), config_pda, badge_mint_pda (writable), mint_authority_pda,
+/// token_2022_program, system_program]
+pub fn initialize_badge_mint(program_id: &Pubkey, admin: &Pubkey, level: u8) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::InitializeBadgeMint { level },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_badge_mint_pda(program_id, level).0, false),
+            AccountMeta::new_readonly(find_mint_authority_pda(program_id).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// SetReasonCodes: [admin (signer, writable), config_pda, reasons_pda (writable), system_program]
+pub fn set_reason_codes(program_id: &Pubkey, admin: &Pubkey, codes: [u16; MAX_REASON_CODES]) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::SetReasonCodes { codes },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_reason_codes_pda(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// AddVerifier: [admin (signer, writable), config_pda, verifier, verifier_pda (writable), system_program]
+///
+/// `weight` — множитель начислений в базисных пунктах (10_000 = 1x).
+pub fn add_verifier(program_id: &Pubkey, admin: &Pubkey, verifier: &Pubkey, weight: u16) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::AddVerifier { weight },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new_readonly(*verifier, false),
+            AccountMeta::new(find_verifier_pda(program_id, verifier).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// RemoveVerifier: [admin (signer), config_pda, verifier, verifier_pda (writable)]
+pub fn remove_verifier(program_id: &Pubkey, admin: &Pubkey, verifier: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::RemoveVerifier,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new_readonly(*verifier, false),
+            AccountMeta::new(find_verifier_pda(program_id, verifier).0, false),
+        ],
+    )
+}
+
+/// ChallengeContribution: [challenger (signer, writable), config_pda, user_pda (writable), contribution_pda,
+/// challenge_pda (writable), system_program, stats_pda (writable)]
+pub fn challenge_contribution(
+    program_id: &Pubkey,
+    challenger: &Pubkey,
+    contributor: &Pubkey,
+    contribution_id: u64,
+) -> Instruction {
+    let (contribution_pda, _) = find_contribution_pda(program_id, contributor, contribution_id);
+    build(
+        program_id,
+        &VertaInstruction::ChallengeContribution { contribution_id },
+        vec![
+            AccountMeta::new(*challenger, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_user_pda(program_id, contributor).0, false),
+            AccountMeta::new_readonly(contribution_pda, false),
+            AccountMeta::new(find_challenge_pda(program_id, &contribution_pda).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// ResolveChallenge: [admin (signer, writable), config_pda, challenge_pda (writable), user_pda (writable),
+/// challenger (writable), treasury_pda (writable), system_program, stats_pda (writable)]
+pub fn resolve_challenge(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    contributor: &Pubkey,
+    contribution_id: u64,
+    challenger: &Pubkey,
+    upheld: bool,
+) -> Instruction {
+    let (contribution_pda, _) = find_contribution_pda(program_id, contributor, contribution_id);
+    build(
+        program_id,
+        &VertaInstruction::ResolveChallenge { upheld },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_challenge_pda(program_id, &contribution_pda).0, false),
+            AccountMeta::new(find_user_pda(program_id, contributor).0, false),
+            AccountMeta::new(*challenger, false),
+            AccountMeta::new(find_treasury_pda(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// GetProgramInfo: аккаунты не требуются
+pub fn get_program_info(program_id: &Pubkey) -> Instruction {
+    build(program_id, &VertaInstruction::GetProgramInfo, vec![])
+}
+
+/// SlashKarma: [moderator (signer, writable), config_pda (writable), user_pda (writable), log_pda (writable), system_program,
+/// stats_pda (writable)]
+///
+/// `log_index` — текущее значение `ConfigAccount::moderation_log_len`.
+pub fn slash_karma(
+    program_id: &Pubkey,
+    moderator: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    reason_code: u16,
+    reason_hash: [u8; 32],
+    log_index: u64,
+) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::SlashKarma {
+            amount,
+            reason_code,
+            reason_hash,
+        },
+        vec![
+            AccountMeta::new(*moderator, true),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new(find_moderation_log_pda(program_id, log_index).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// FreezeUser: [moderator (signer, writable), config_pda (writable), user_pda (writable), log_pda (writable), system_program]
+///
+/// `log_index` — текущее значение `ConfigAccount::moderation_log_len`.
+pub fn freeze_user(
+    program_id: &Pubkey,
+    moderator: &Pubkey,
+    user: &Pubkey,
+    reason_code: u16,
+    reason_hash: [u8; 32],
+    log_index: u64,
+) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::FreezeUser {
+            reason_code,
+            reason_hash,
+        },
+        moderation_accounts(program_id, moderator, user, log_index),
+    )
+}
+
+/// UnfreezeUser: те же аккаунты, что и у FreezeUser
+pub fn unfreeze_user(program_id: &Pubkey, moderator: &Pubkey, user: &Pubkey, log_index: u64) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::UnfreezeUser,
+        moderation_accounts(program_id, moderator, user, log_index),
+    )
+}
+
+/// IssueStrike: те же аккаунты, что и у FreezeUser
+pub fn issue_strike(
+    program_id: &Pubkey,
+    moderator: &Pubkey,
+    user: &Pubkey,
+    reason_code: u16,
+    reason_hash: [u8; 32],
+    log_index: u64,
+) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::IssueStrike {
+            reason_code,
+            reason_hash,
+        },
+        moderation_accounts(program_id, moderator, user, log_index),
+    )
+}
+
+fn moderation_accounts(program_id: &Pubkey, moderator: &Pubkey, user: &Pubkey, log_index: u64) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*moderator, true),
+        AccountMeta::new(find_config_pda(program_id).0, false),
+        AccountMeta::new(find_user_pda(program_id, user).0, false),
+        AccountMeta::new(find_moderation_log_pda(program_id, log_index).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]
+}
+
+/// TransferKarma: [sender (signer, writable), sender_pda (writable), recipient_pda (writable), config_pda, treasury_pda (writable), system_program,
+/// stats_pda (writable)]
+pub fn transfer_karma(program_id: &Pubkey, sender: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    let (sender_pda, _) = find_user_pda(program_id, sender);
+    let (recipient_pda, _) = find_user_pda(program_id, recipient);
+    build(
+        program_id,
+        &VertaInstruction::TransferKarma { amount },
+        vec![
+            AccountMeta::new(*sender, true),
+            AccountMeta::new(sender_pda, false),
+            AccountMeta::new(recipient_pda, false),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_treasury_pda(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// CloseUserAccount: [user (signer, writable), user_pda (writable), stats_pda (writable),
+/// rent_payer (writable, если не совпадает с `user`)]
+///
+/// `rent_payer` — `UserAccount::rent_payer`: тот, кто вносил ренту при регистрации, получает её обратно.
+pub fn close_user_account(program_id: &Pubkey, user: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(user_pda, false),
+        AccountMeta::new(find_global_stats_pda(program_id).0, false),
+    ];
+    if rent_payer != user {
+        accounts.push(AccountMeta::new(*rent_payer, false));
+    }
+    build(program_id, &VertaInstruction::CloseUserAccount, accounts)
+}
+
+/// DecayKarma: [user_pda (writable), config_pda, stats_pda (writable)]
+pub fn decay_karma(program_id: &Pubkey, user: &Pubkey) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::DecayKarma,
+        vec![
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// AddKarmaWithApprovals: [submitter (signer), config_pda, user_pda (writable), instructions_sysvar, stats_pda (writable)]
+///
+/// Перед этой инструкцией в транзакции должна стоять инструкция Ed25519 с подписями одобрений.
+pub fn add_karma_with_approvals(
+    program_id: &Pubkey,
+    submitter: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    category: u8,
+    envelope: GrantEnvelope,
+) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::AddKarmaWithApprovals {
+            amount,
+            category,
+            envelope,
+        },
+        vec![
+            AccountMeta::new_readonly(*submitter, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// MigrateAccount: [payer (signer, writable), user_pda (writable), system_program, user]
+///
+/// Кошелёк `user` нужен схемам, которые не хранили владельца в данных; остальные его не читают.
+pub fn migrate_account(program_id: &Pubkey, payer: &Pubkey, user: &Pubkey) -> Instruction {
+    let (user_pda, _) = find_user_pda(program_id, user);
+    build(
+        program_id,
+        &VertaInstruction::MigrateAccount,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(user_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*user, false),
+        ],
+    )
+}
+
+/// MigrateConfig: [payer (signer, writable), config_pda (writable), system_program]
+pub fn migrate_config(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::MigrateConfig,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(find_config_pda(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Endorse: [endorser (signer, writable), endorser_pda (writable), endorsee_pda (writable), config_pda,
+/// endorsement_pda (writable), reverse_endorsement_pda, system_program, stats_pda (writable)]
+pub fn endorse(program_id: &Pubkey, endorser: &Pubkey, endorsee: &Pubkey, weight: u64) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::Endorse { weight },
+        vec![
+            AccountMeta::new(*endorser, true),
+            AccountMeta::new(find_user_pda(program_id, endorser).0, false),
+            AccountMeta::new(find_user_pda(program_id, endorsee).0, false),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_endorsement_pda(program_id, endorser, endorsee).0, false),
+            AccountMeta::new_readonly(find_endorsement_pda(program_id, endorsee, endorser).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+        ],
+    )
+}
+
+/// WithdrawTreasury: [admin (signer, writable), config_pda, treasury_pda (writable), destination (writable),
+/// stats_pda (writable), system_program]
+pub fn withdraw_treasury(program_id: &Pubkey, admin: &Pubkey, destination: &Pubkey, lamports: u64) -> Instruction {
+    build(
+        program_id,
+        &VertaInstruction::WithdrawTreasury { lamports },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(find_config_pda(program_id).0, false),
+            AccountMeta::new(find_treasury_pda(program_id).0, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new(find_global_stats_pda(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
@@ -0,0 +1,148 @@
+// Клиентский SDK для программы Verta.
+// Содержит вспомогательные функции для работы с аккаунтами программы через RPC.
+
+pub mod approvals;
+pub mod cache;
+pub mod client;
+pub mod confirm;
+pub mod dedupe;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod fixtures;
+pub mod info;
+pub mod instruction;
+pub mod partial;
+pub mod scan;
+pub mod sender;
+pub mod verify;
+
+pub use cache::{AccountCache, CachePolicy};
+pub use client::{ClusterInfo, VertaClient};
+pub use confirm::{ConfirmationConfig, ConfirmationMethod};
+pub use scan::{AccountScanner, ScanConfig, ScannableAccount};
+pub use sender::{TxOutcome, TxRequest, TxSender};
+pub use error::{decode_custom_error, Error, Result};
+pub use export::TransactionPayload;
+
+use solana_sdk::{bpf_loader_upgradeable, pubkey::Pubkey};
+
+/// Сид PDA аккаунта пользователя (должен совпадать с сидом в программе)
+pub const USER_SEED: &[u8] = b"user";
+
+/// Находит адрес PDA аккаунта пользователя и его бамп.
+pub fn find_user_pda(program_id: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USER_SEED, wallet.as_ref()], program_id)
+}
+
+/// Сид PDA записи о подтверждённом вкладе
+pub const CONTRIBUTION_SEED: &[u8] = b"contribution";
+
+/// Находит адрес PDA записи о вкладе `contribution_id` пользователя `contributor`.
+pub fn find_contribution_pda(program_id: &Pubkey, contributor: &Pubkey, contribution_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CONTRIBUTION_SEED, contributor.as_ref(), &contribution_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Сид PDA глобальной конфигурации программы
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Находит адрес PDA глобальной конфигурации.
+pub fn find_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Сид PDA казны программы
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Находит адрес PDA казны.
+pub fn find_treasury_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], program_id)
+}
+
+/// Сид PDA глобальной статистики
+pub const GLOBAL_STATS_SEED: &[u8] = b"stats";
+
+/// Находит адрес PDA глобальной статистики.
+pub fn find_global_stats_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_STATS_SEED], program_id)
+}
+
+/// Сид PDA записи журнала модерации
+pub const MODERATION_LOG_SEED: &[u8] = b"modlog";
+
+/// Находит адрес PDA записи журнала модерации с номером `index`.
+pub fn find_moderation_log_pda(program_id: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MODERATION_LOG_SEED, &index.to_le_bytes()], program_id)
+}
+
+/// Сид PDA учёта начислений верификатора
+pub const VERIFIER_SEED: &[u8] = b"verifier";
+
+/// Находит адрес PDA учёта начислений верификатора.
+pub fn find_verifier_pda(program_id: &Pubkey, verifier: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VERIFIER_SEED, verifier.as_ref()], program_id)
+}
+
+/// Сид PDA, которому принадлежит mint authority токена награды за уровень
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+
+/// Находит адрес PDA mint authority токена награды.
+pub fn find_mint_authority_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id)
+}
+
+/// Сид PDA mint бейджа за уровень
+pub const BADGE_SEED: &[u8] = b"badge";
+
+/// Находит адрес PDA mint бейджа за уровень `level` (Token-2022).
+pub fn find_badge_mint_pda(program_id: &Pubkey, level: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BADGE_SEED, &[level]], program_id)
+}
+
+/// Сид PDA mint NFT-бейджа Metaplex
+pub const BADGE_NFT_SEED: &[u8] = b"badge_nft";
+
+/// Находит адрес PDA mint NFT-бейджа владельца `wallet`.
+pub fn find_badge_nft_mint_pda(program_id: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BADGE_NFT_SEED, wallet.as_ref()], program_id)
+}
+
+/// Сид PDA индекса шарда пользователей
+pub const SHARD_INDEX_SEED: &[u8] = b"shard";
+
+/// Находит адрес PDA индекса шарда `shard` (см. `crate::user_shard`).
+pub fn find_shard_index_pda(program_id: &Pubkey, shard: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SHARD_INDEX_SEED, &[shard]], program_id)
+}
+
+/// Сид PDA таблицы кодов причин начисления
+pub const REASON_CODES_SEED: &[u8] = b"reasons";
+
+/// Находит адрес PDA таблицы кодов причин.
+pub fn find_reason_codes_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REASON_CODES_SEED], program_id)
+}
+
+/// Сид PDA записи спора по вкладу
+pub const CHALLENGE_SEED: &[u8] = b"challenge";
+
+/// Находит адрес PDA спора по вкладу с PDA `contribution_pda`.
+pub fn find_challenge_pda(program_id: &Pubkey, contribution_pda: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CHALLENGE_SEED, contribution_pda.as_ref()], program_id)
+}
+
+/// Сид PDA записи поручительства
+pub const ENDORSEMENT_SEED: &[u8] = b"endorsement";
+
+/// Находит адрес PDA поручительства `endorser` за `endorsee`.
+pub fn find_endorsement_pda(program_id: &Pubkey, endorser: &Pubkey, endorsee: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ENDORSEMENT_SEED, endorser.as_ref(), endorsee.as_ref()], program_id)
+}
+
+/// Находит адрес ProgramData аккаунта программы (сиды [program_id] у BPF Loader Upgradeable).
+pub fn find_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
@@ -0,0 +1,76 @@
+// Частичная загрузка аккаунтов через dataSlice.
+// Для таблиц лидеров и отслеживания кармы не нужны аккаунты целиком:
+// RPC возвращает только запрошенный диапазон байт, что заметно сокращает трафик.
+
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::pubkey::Pubkey;
+
+use super::{
+    client::VertaClient,
+    error::{Error, Result},
+};
+use crate::{UserAccount, VertaAccount};
+
+impl VertaClient {
+    fn slice_config(&self, offset: usize, length: usize) -> RpcAccountInfoConfig {
+        RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig { offset, length }),
+            commitment: Some(self.commitment()),
+            min_context_slot: None,
+        }
+    }
+
+    /// Загружает диапазон байт `[offset, offset + length)` данных аккаунта.
+    pub fn get_account_slice(&self, address: &Pubkey, offset: usize, length: usize) -> Result<Vec<u8>> {
+        let account = self
+            .rpc()
+            .get_account_with_config(address, self.slice_config(offset, length))?
+            .value
+            .ok_or(Error::AccountNotFound(*address))?;
+        if account.owner != *self.program_id() {
+            return Err(Error::InvalidOwner {
+                account: *address,
+                owner: account.owner,
+            });
+        }
+        Ok(account.data)
+    }
+
+    /// Загружает только карму пользователя (8 байт вместо всего аккаунта).
+    pub fn get_user_karma(&self, wallet: &Pubkey) -> Result<u64> {
+        let address = self.user_pda(wallet);
+        let data = self.get_account_slice(&address, UserAccount::KARMA_OFFSET, 8)?;
+        read_u64(&address, &data)
+    }
+
+    /// Загружает карму всех пользователей одним запросом getProgramAccounts.
+    /// Возвращает пары (адрес PDA, карма).
+    pub fn get_all_user_karma(&self) -> Result<Vec<(Pubkey, u64)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &UserAccount::DISCRIMINATOR,
+            ))]),
+            account_config: self.slice_config(UserAccount::KARMA_OFFSET, 8),
+            ..RpcProgramAccountsConfig::default()
+        };
+        self.rpc()
+            .get_program_accounts_with_config(self.program_id(), config)?
+            .into_iter()
+            .map(|(address, account)| Ok((address, read_u64(&address, &account.data)?)))
+            .collect()
+    }
+}
+
+fn read_u64(address: &Pubkey, data: &[u8]) -> Result<u64> {
+    let bytes: [u8; 8] = data.try_into().map_err(|_| Error::Decode {
+        account: *address,
+        source: std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "account data slice is too short"),
+    })?;
+    Ok(u64::from_le_bytes(bytes))
+}
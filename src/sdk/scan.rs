@@ -0,0 +1,184 @@
+// Постраничное сканирование аккаунтов программы.
+// Один getProgramAccounts по всей программе на больших объёмах упирается в лимиты
+// размера ответа RPC. Сканер разбивает запрос на части по байту с известным смещением
+// (memcmp-фильтр), повторяет неудачные запросы с задержкой и выдаёт декодированные
+// аккаунты по мере загрузки частей.
+
+use std::{collections::VecDeque, marker::PhantomData, thread, time::Duration};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use super::{
+    client::VertaClient,
+    error::{Error, Result},
+};
+use crate::{
+    ChallengeAccount, ContributionAccount, EndorsementAccount, ModerationLogAccount, ShardIndexAccount, UserAccount, VerifierAccount,
+    VertaAccount, DISCRIMINATOR_LEN,
+};
+
+/// Тип аккаунта программы, который можно найти сканированием.
+pub trait ScannableAccount: VertaAccount {
+    /// Смещение неизменяемого байта с равномерным распределением значений,
+    /// по которому запрос разбивается на 256 частей (None — один запрос).
+    const PARTITION_OFFSET: Option<usize> = None;
+
+    /// Фильтры, выделяющие аккаунты этого типа среди всех аккаунтов программы.
+    fn filters() -> Vec<RpcFilterType> {
+        vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &Self::DISCRIMINATOR))]
+    }
+}
+
+impl ScannableAccount for UserAccount {
+    // Первый байт кошелька владельца распределён равномерно; он же номер шарда (crate::user_shard)
+    const PARTITION_OFFSET: Option<usize> = Some(UserAccount::OWNER_OFFSET);
+}
+
+impl ScannableAccount for ContributionAccount {
+    // Первый байт кошелька автора вклада
+    const PARTITION_OFFSET: Option<usize> = Some(DISCRIMINATOR_LEN);
+}
+
+impl ScannableAccount for ModerationLogAccount {}
+
+impl ScannableAccount for VerifierAccount {}
+
+impl ScannableAccount for EndorsementAccount {}
+
+impl ScannableAccount for ShardIndexAccount {}
+
+impl ScannableAccount for ChallengeAccount {}
+
+/// Параметры повторов при сканировании.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScanConfig {
+    /// Сколько раз повторять неудавшийся запрос части
+    pub max_retries: u32,
+    /// Задержка перед первым повтором (удваивается с каждой попыткой)
+    pub retry_backoff: Duration,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Итератор по декодированным аккаунтам типа `T`.
+pub struct AccountScanner<'a, T> {
+    client: &'a VertaClient,
+    config: ScanConfig,
+    // Следующая часть для загрузки; None — все части загружены
+    next_partition: Option<u16>,
+    // Последняя часть, которую нужно загрузить
+    last_partition: u16,
+    buffer: VecDeque<(Pubkey, Account)>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: ScannableAccount> AccountScanner<'a, T> {
+    fn new(client: &'a VertaClient, config: ScanConfig, partitions: (u8, u8)) -> Self {
+        Self {
+            client,
+            config,
+            next_partition: Some(partitions.0 as u16),
+            last_partition: partitions.1 as u16,
+            buffer: VecDeque::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    // Загружает очередную часть, повторяя запрос при ошибках
+    fn fetch_partition(&self, partition: u16) -> Result<Vec<(Pubkey, Account)>> {
+        let mut filters = T::filters();
+        if let Some(offset) = T::PARTITION_OFFSET {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                offset,
+                &[partition as u8],
+            )));
+        }
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let mut backoff = self.config.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            match self
+                .client
+                .rpc()
+                .get_program_accounts_with_config(self.client.program_id(), config.clone())
+            {
+                Ok(accounts) => return Ok(accounts),
+                Err(err) if attempt >= self.config.max_retries => return Err(err.into()),
+                Err(_) => {
+                    attempt += 1;
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+            }
+        }
+    }
+}
+
+impl<T: ScannableAccount> Iterator for AccountScanner<'_, T> {
+    type Item = Result<(Pubkey, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.is_empty() {
+            let partition = self.next_partition?;
+            self.next_partition = match T::PARTITION_OFFSET {
+                Some(_) if partition < self.last_partition => Some(partition + 1),
+                _ => None,
+            };
+            match self.fetch_partition(partition) {
+                Ok(accounts) => self.buffer.extend(accounts),
+                Err(err) => {
+                    self.next_partition = None;
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        let (address, account) = self.buffer.pop_front()?;
+        let decoded = T::try_from_account_data(&account.data)
+            .map(|value| (address, value))
+            .map_err(|source| Error::Decode {
+                account: address,
+                source,
+            });
+        Some(decoded)
+    }
+}
+
+impl VertaClient {
+    /// Сканирует все аккаунты программы типа `T`.
+    pub fn scan<T: ScannableAccount>(&self) -> AccountScanner<'_, T> {
+        self.scan_with_config(ScanConfig::default())
+    }
+
+    pub fn scan_with_config<T: ScannableAccount>(&self, config: ScanConfig) -> AccountScanner<'_, T> {
+        AccountScanner::new(self, config, (0, u8::MAX))
+    }
+
+    /// Сканирует аккаунты типа `T` одной части `partition` (байт по смещению PARTITION_OFFSET).
+    /// Для UserAccount часть совпадает с шардом пользователя: так кранки и индексеры
+    /// обрабатывают пользователей по шардам. Для типов без PARTITION_OFFSET сканирует всё.
+    pub fn scan_partition<T: ScannableAccount>(&self, partition: u8, config: ScanConfig) -> AccountScanner<'_, T> {
+        AccountScanner::new(self, config, (partition, partition))
+    }
+}
@@ -0,0 +1,145 @@
+// Пакетная отправка транзакций с ограниченной параллельностью.
+// Транзакции берутся из источника по одной, только когда освобождается один из
+// `concurrency` потоков, поэтому источник не вычитывается быстрее, чем транзакции
+// подтверждаются (обратное давление). Каждая транзакция подписывается со свежим
+// blockhash; если он истёк, а транзакция так и не попала в блок (подпись не найдена даже
+// на уровне processed), она переподписывается и отправляется снова.
+
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signature},
+};
+
+use super::{
+    client::VertaClient,
+    confirm::ConfirmationConfig,
+    error::{Error, Result},
+};
+
+/// Число одновременно отправляемых транзакций по умолчанию
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Сколько раз по умолчанию переотправлять транзакцию с истёкшим blockhash
+pub const DEFAULT_MAX_RESUBMITS: u32 = 3;
+
+/// Транзакция в очереди на отправку: инструкции и подписанты помимо плательщика.
+#[derive(Clone, Debug)]
+pub struct TxRequest {
+    pub instructions: Vec<Instruction>,
+    pub signers: Vec<Arc<Keypair>>,
+}
+
+impl TxRequest {
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Self {
+            instructions,
+            signers: Vec::new(),
+        }
+    }
+
+    pub fn with_signer(mut self, signer: Arc<Keypair>) -> Self {
+        self.signers.push(signer);
+        self
+    }
+}
+
+/// Результат отправки одной транзакции.
+#[derive(Debug)]
+pub struct TxOutcome {
+    /// Порядковый номер транзакции в источнике
+    pub index: usize,
+    pub result: Result<Signature>,
+    /// Сколько раз транзакция отправлялась (1 — без переотправок)
+    pub attempts: u32,
+}
+
+/// Отправитель пакетов транзакций от имени одного плательщика.
+pub struct TxSender {
+    client: VertaClient,
+    payer: Arc<Keypair>,
+    concurrency: usize,
+    max_resubmits: u32,
+    confirmation: ConfirmationConfig,
+}
+
+impl TxSender {
+    /// Создаёт отправитель с параметрами подтверждения клиента.
+    pub fn new(client: VertaClient, payer: Arc<Keypair>) -> Self {
+        let confirmation = *client.confirmation();
+        Self {
+            client,
+            payer,
+            concurrency: DEFAULT_CONCURRENCY,
+            max_resubmits: DEFAULT_MAX_RESUBMITS,
+            confirmation,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_max_resubmits(mut self, max_resubmits: u32) -> Self {
+        self.max_resubmits = max_resubmits;
+        self
+    }
+
+    pub fn with_confirmation(mut self, confirmation: ConfirmationConfig) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// Отправляет все транзакции источника и возвращает результаты в порядке источника.
+    /// `on_outcome` вызывается сразу после завершения каждой транзакции (для прогресса).
+    pub fn send_all<I>(&self, requests: I, on_outcome: impl Fn(&TxOutcome) + Sync) -> Vec<TxOutcome>
+    where
+        I: IntoIterator<Item = TxRequest>,
+        I::IntoIter: Send,
+    {
+        let requests = Mutex::new(requests.into_iter().enumerate());
+        let outcomes = Mutex::new(Vec::new());
+        let on_outcome = &on_outcome;
+
+        thread::scope(|scope| {
+            for _ in 0..self.concurrency {
+                scope.spawn(|| loop {
+                    let Some((index, request)) = requests.lock().unwrap().next() else {
+                        break;
+                    };
+                    let outcome = self.send_one(index, &request);
+                    on_outcome(&outcome);
+                    outcomes.lock().unwrap().push(outcome);
+                });
+            }
+        });
+
+        let mut outcomes = outcomes.into_inner().unwrap();
+        outcomes.sort_by_key(|outcome| outcome.index);
+        outcomes
+    }
+
+    // Отправляет транзакцию, переподписывая её, пока blockhash истекает до подтверждения
+    fn send_one(&self, index: usize, request: &TxRequest) -> TxOutcome {
+        let signers: Vec<&Keypair> = request.signers.iter().map(Arc::as_ref).collect();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = self.client.send_instructions_with_config(
+                &request.instructions,
+                &self.payer,
+                &signers,
+                &self.confirmation,
+            );
+            match result {
+                Err(Error::BlockhashExpired(_)) if attempts <= self.max_resubmits => continue,
+                result => return TxOutcome { index, result, attempts },
+            }
+        }
+    }
+}
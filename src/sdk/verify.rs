@@ -0,0 +1,30 @@
+// Проверка того, что задеплоенная программа собрана из известного исходного кода.
+// Хеш считается так же, как в solana-verify: sha256 от исполняемого файла без
+// завершающих нулевых байтов (ProgramData аккаунт дополнен нулями до выделенного размера).
+
+use solana_sdk::{
+    bpf_loader_upgradeable::UpgradeableLoaderState,
+    hash::{hash, Hash},
+};
+
+use super::{
+    client::VertaClient,
+    error::{Error, Result},
+    find_program_data_address,
+};
+
+/// Хеш исполняемого файла программы, совместимый с solana-verify.
+pub fn executable_hash(executable: &[u8]) -> Hash {
+    let end = executable.iter().rposition(|&byte| byte != 0).map_or(0, |last| last + 1);
+    hash(&executable[..end])
+}
+
+impl VertaClient {
+    /// Хеш исполняемого файла программы, записанного в её ProgramData аккаунте.
+    pub fn deployed_program_hash(&self) -> Result<Hash> {
+        let address = find_program_data_address(self.program_id());
+        let program_data = self.get_account(&address)?.ok_or(Error::AccountNotFound(address))?;
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        Ok(executable_hash(program_data.data.get(metadata_len..).unwrap_or_default()))
+    }
+}
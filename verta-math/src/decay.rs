@@ -0,0 +1,58 @@
+// Экспоненциальное затухание кармы с заданным периодом полураспада
+
+use crate::fixed::{self, SCALE};
+
+// 2^(-1/2^i) в фиксированной точке для i = 1..=20: множители для двоичных знаков
+// дробной части показателя. Точности 20 знаков хватает, чтобы ошибка была меньше 1e-6.
+const NEG_EXP2_FRACTIONS: [u128; 20] = [
+    707_106_781,
+    840_896_415,
+    917_004_043,
+    957_603_281,
+    978_572_062,
+    989_228_013,
+    994_599_423,
+    997_296_056,
+    998_647_113,
+    999_323_328,
+    999_661_606,
+    999_830_789,
+    999_915_391,
+    999_957_695,
+    999_978_847,
+    999_989_423,
+    999_994_712,
+    999_997_356,
+    999_998_678,
+    999_999_339,
+];
+
+/// Значение `amount` после `elapsed` секунд затухания с периодом полураспада `half_life`:
+/// `amount * 2^(-elapsed / half_life)`, с округлением вниз.
+/// При `half_life == 0` затухание выключено и значение не меняется.
+pub fn decay(amount: u64, elapsed: u64, half_life: u64) -> u64 {
+    if half_life == 0 || elapsed == 0 {
+        return amount;
+    }
+
+    // Целые периоды полураспада — сдвиг вправо
+    let whole = elapsed / half_life;
+    if whole >= u64::BITS as u64 {
+        return 0;
+    }
+    let halved = amount >> whole;
+
+    // Дробная часть показателя: по одному двоичному знаку за итерацию
+    let mut fraction = (elapsed % half_life) as u128 * SCALE / half_life as u128;
+    let mut factor = SCALE;
+    for multiplier in NEG_EXP2_FRACTIONS {
+        fraction *= 2;
+        if fraction >= SCALE {
+            fraction -= SCALE;
+            // factor и multiplier не больше SCALE, произведение не переполняется
+            factor = fixed::mul(factor, multiplier).unwrap_or(0);
+        }
+    }
+
+    fixed::to_int_floor(fixed::mul(fixed::from_int(halved), factor).unwrap_or(0)) as u64
+}
@@ -0,0 +1,170 @@
+// Арифметика с фиксированной точкой (десятичная, 9 знаков после запятой).
+// Значение x хранится как x * SCALE в u128; промежуточные вычисления не переполняются
+// для множителей до u64::MAX. Все функции возвращают None при переполнении.
+
+/// Единица в фиксированной точке.
+pub const SCALE: u128 = 1_000_000_000;
+
+/// Перевод целого числа в фиксированную точку.
+pub fn from_int(value: u64) -> u128 {
+    value as u128 * SCALE
+}
+
+/// Целая часть числа с фиксированной точкой (с округлением вниз).
+pub fn to_int_floor(value: u128) -> u128 {
+    value / SCALE
+}
+
+/// Произведение a * b.
+pub fn mul(a: u128, b: u128) -> Option<u128> {
+    a.checked_mul(b).map(|product| product / SCALE)
+}
+
+/// Частное a / b.
+pub fn div(a: u128, b: u128) -> Option<u128> {
+    if b == 0 {
+        return None;
+    }
+    a.checked_mul(SCALE).map(|scaled| scaled / b)
+}
+
+/// Степень base^exp (возведение в квадрат и умножение).
+pub fn pow(base: u128, mut exp: u32) -> Option<u128> {
+    let mut result = SCALE;
+    let mut factor = base;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, factor)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            factor = mul(factor, factor)?;
+        }
+    }
+    Some(result)
+}
+
+/// Двоичный логарифм log2(x) для x >= 1.
+/// Целая часть находится делением на 2, дробная — по одному двоичному знаку
+/// за итерацию через возведение в квадрат.
+pub fn log2(x: u128) -> Option<u128> {
+    if x < SCALE {
+        return None;
+    }
+    let mut result = 0;
+    let mut y = x;
+    while y >= 2 * SCALE {
+        y /= 2;
+        result += SCALE;
+    }
+    let mut bit = SCALE / 2;
+    while bit > 0 {
+        // y < 2, поэтому y * y < 4 * SCALE^2 и не переполняет u128
+        y = y * y / SCALE;
+        if y >= 2 * SCALE {
+            y /= 2;
+            result += bit;
+        }
+        bit /= 2;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Погрешность log2: дробные знаки теряют младшие разряды при каждом возведении в квадрат
+    const LOG2_TOLERANCE: u128 = 64;
+
+    fn assert_close(actual: u128, expected: u128, tolerance: u128) {
+        let diff = actual.abs_diff(expected);
+        assert!(diff <= tolerance, "{} differs from {} by {}", actual, expected, diff);
+    }
+
+    #[test]
+    fn int_round_trip() {
+        for value in [0, 1, 7, 1_000, 123_456_789, u64::MAX] {
+            assert_eq!(to_int_floor(from_int(value)), value as u128);
+        }
+        assert_eq!(to_int_floor(SCALE - 1), 0);
+        assert_eq!(to_int_floor(SCALE + SCALE / 2), 1);
+    }
+
+    #[test]
+    fn mul_div() {
+        // (a, b, a * b)
+        let cases = [
+            (from_int(2), from_int(3), from_int(6)),
+            (SCALE / 2, from_int(4), from_int(2)),
+            (SCALE + SCALE / 2, SCALE + SCALE / 2, 2 * SCALE + SCALE / 4),
+            (SCALE, SCALE, SCALE),
+            (SCALE + 1, SCALE, SCALE + 1),
+            (0, from_int(5), 0),
+        ];
+        for (a, b, product) in cases {
+            assert_eq!(mul(a, b), Some(product));
+            assert_eq!(mul(b, a), Some(product));
+            if b != 0 {
+                assert_close(div(product, b).unwrap(), a, 1);
+            }
+            if a != 0 {
+                assert_close(div(product, a).unwrap(), b, 1);
+            }
+        }
+        assert_eq!(div(from_int(1), 0), None);
+        assert_eq!(mul(u128::MAX, 2), None);
+        assert_eq!(div(u128::MAX, SCALE), None);
+    }
+
+    #[test]
+    fn pow_table() {
+        // (base, exp, base^exp)
+        let cases = [
+            (from_int(2), 0, SCALE),
+            (from_int(2), 1, from_int(2)),
+            (from_int(2), 10, from_int(1024)),
+            (from_int(2), 63, from_int(1 << 63)),
+            (SCALE + SCALE / 2, 2, 2 * SCALE + SCALE / 4),
+            (SCALE, 1_000, SCALE),
+            (SCALE / 2, 3, SCALE / 8),
+            (0, 5, 0),
+        ];
+        for (base, exp, expected) in cases {
+            assert_eq!(pow(base, exp), Some(expected), "{} ^ {}", base, exp);
+        }
+        assert_eq!(pow(from_int(u64::MAX), 4), None);
+    }
+
+    #[test]
+    fn log2_table() {
+        // (x, log2(x))
+        let cases = [
+            (SCALE, 0),
+            (from_int(2), SCALE),
+            (from_int(8), from_int(3)),
+            (from_int(1024), from_int(10)),
+            (1_414_213_562, SCALE / 2),
+            (from_int(3), 1_584_962_500),
+        ];
+        for (x, expected) in cases {
+            assert_close(log2(x).unwrap(), expected, LOG2_TOLERANCE);
+        }
+        assert_eq!(log2(SCALE - 1), None);
+        assert_eq!(log2(0), None);
+    }
+
+    #[test]
+    fn log2_inverts_pow() {
+        for exp in [0, 1, 2, 10, 40, 60] {
+            assert_eq!(log2(pow(from_int(2), exp).unwrap()), Some(from_int(exp as u64)));
+        }
+        // Множители около единицы: log2(g^n) = n * log2(g)
+        for growth in [SCALE + 1, SCALE + 1_000, SCALE + SCALE / 100, 1_150_000_000] {
+            for exp in [1, 10, 100] {
+                let expected = log2(growth).unwrap() * exp as u128;
+                assert_close(log2(pow(growth, exp).unwrap()).unwrap(), expected, exp as u128 * LOG2_TOLERANCE);
+            }
+        }
+    }
+}
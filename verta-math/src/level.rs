@@ -0,0 +1,206 @@
+// Кривая уровней: зависимость уровня от кармы
+
+use crate::fixed;
+
+/// Карма, необходимая для каждого следующего уровня.
+pub const KARMA_PER_LEVEL: u64 = 1000;
+
+/// Максимальный уровень по умолчанию.
+pub const DEFAULT_MAX_LEVEL: u8 = 100;
+
+/// Форма кривой уровней.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LevelCurve {
+    /// Каждый уровень стоит одинаково: `karma_per_level`.
+    Linear { karma_per_level: u64 },
+    /// Каждый следующий уровень дороже предыдущего в `growth` раз:
+    /// первый уровень стоит `base_karma`, порог уровня L равен
+    /// base_karma * (growth^L - 1) / (growth - 1).
+    /// `growth` задаётся в фиксированной точке (`fixed::SCALE` = 1.0) и должен быть > 1.
+    /// Ранние уровни даются быстро, поздние — редко.
+    Geometric { base_karma: u64, growth: u64 },
+}
+
+impl Default for LevelCurve {
+    fn default() -> Self {
+        LevelCurve::Linear {
+            karma_per_level: KARMA_PER_LEVEL,
+        }
+    }
+}
+
+impl LevelCurve {
+    /// Корректны ли параметры: стоимость уровня положительна, у геометрической кривой growth > 1.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            LevelCurve::Linear { karma_per_level } => karma_per_level > 0,
+            LevelCurve::Geometric { base_karma, growth } => base_karma > 0 && growth as u128 > fixed::SCALE,
+        }
+    }
+
+    /// Минимальная карма для достижения уровня (u64::MAX, если порог недостижим).
+    pub fn karma_for_level(&self, level: u8) -> u64 {
+        match *self {
+            LevelCurve::Linear { karma_per_level } => karma_per_level.saturating_mul(level as u64),
+            LevelCurve::Geometric { base_karma, growth } => {
+                geometric_threshold(base_karma, growth as u128, level).unwrap_or(u64::MAX)
+            }
+        }
+    }
+
+    /// Уровень, соответствующий количеству кармы (не выше u8::MAX).
+    pub fn level_for_karma(&self, karma: u64) -> u8 {
+        match *self {
+            LevelCurve::Linear { karma_per_level } => {
+                if karma_per_level == 0 {
+                    return u8::MAX;
+                }
+                (karma / karma_per_level).min(u8::MAX as u64) as u8
+            }
+            LevelCurve::Geometric { base_karma, growth } => {
+                let mut level = geometric_level_estimate(base_karma, growth as u128, karma);
+                // Порог u64::MAX означает недостижимый уровень, даже при karma = u64::MAX
+                let reached = |level: u8| {
+                    let threshold = self.karma_for_level(level);
+                    threshold < u64::MAX && threshold <= karma
+                };
+                // Оценка через логарифм приближённая: доводим до точного значения по порогам
+                while level < u8::MAX && reached(level + 1) {
+                    level += 1;
+                }
+                while level > 0 && !reached(level) {
+                    level -= 1;
+                }
+                level
+            }
+        }
+    }
+}
+
+// base * (growth^level - 1) / (growth - 1)
+fn geometric_threshold(base_karma: u64, growth: u128, level: u8) -> Option<u64> {
+    if growth <= fixed::SCALE {
+        return None;
+    }
+    let numerator = fixed::pow(growth, level as u32)? - fixed::SCALE;
+    let ratio = fixed::div(numerator, growth - fixed::SCALE)?;
+    let threshold = fixed::to_int_floor(fixed::mul(fixed::from_int(base_karma), ratio)?);
+    u64::try_from(threshold).ok()
+}
+
+// level ≈ log_growth(karma * (growth - 1) / base + 1)
+fn geometric_level_estimate(base_karma: u64, growth: u128, karma: u64) -> u8 {
+    if base_karma == 0 || growth <= fixed::SCALE {
+        return 0;
+    }
+    let estimate = (|| {
+        let scaled = fixed::div(fixed::mul(fixed::from_int(karma), growth - fixed::SCALE)?, fixed::from_int(base_karma))?;
+        let log_value = fixed::log2(scaled + fixed::SCALE)?;
+        let log_growth = fixed::log2(growth)?;
+        fixed::div(log_value, log_growth).map(fixed::to_int_floor)
+    })();
+    estimate.map_or(0, |level| level.min(u8::MAX as u128) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1.0 в фиксированной точке для поля growth
+    const ONE: u64 = fixed::SCALE as u64;
+
+    const CURVES: [LevelCurve; 6] = [
+        LevelCurve::Linear { karma_per_level: KARMA_PER_LEVEL },
+        LevelCurve::Linear { karma_per_level: 1 },
+        LevelCurve::Geometric { base_karma: 100, growth: 1_150_000_000 },
+        LevelCurve::Geometric { base_karma: 1_000, growth: 2 * ONE },
+        LevelCurve::Geometric { base_karma: 1_000, growth: ONE + 1 },
+        LevelCurve::Geometric { base_karma: 1, growth: ONE + 1_000 },
+    ];
+
+    #[test]
+    fn geometric_threshold_table() {
+        // (base_karma, growth, level, порог)
+        let cases = [
+            (100, 2 * fixed::SCALE, 0, Some(0)),
+            (100, 2 * fixed::SCALE, 1, Some(100)),
+            (100, 2 * fixed::SCALE, 2, Some(300)),
+            (100, 2 * fixed::SCALE, 10, Some(102_300)),
+            (100, 1_500_000_000, 3, Some(475)),
+            (1_000, fixed::SCALE + 1, 1, Some(1_000)),
+            (1_000, fixed::SCALE + 1, 100, Some(100_000)),
+            (u64::MAX, 2 * fixed::SCALE, 2, None),
+            (100, fixed::SCALE, 5, None),
+            (100, fixed::SCALE - 1, 5, None),
+        ];
+        for (base_karma, growth, level, expected) in cases {
+            assert_eq!(geometric_threshold(base_karma, growth, level), expected, "{} {} {}", base_karma, growth, level);
+        }
+    }
+
+    #[test]
+    fn thresholds_are_monotonic() {
+        for curve in CURVES {
+            for level in 0..u8::MAX {
+                assert!(curve.karma_for_level(level) <= curve.karma_for_level(level + 1), "{:?} at {}", curve, level);
+            }
+        }
+    }
+
+    #[test]
+    fn level_for_karma_inverts_karma_for_level() {
+        for curve in CURVES {
+            for level in 0..=u8::MAX {
+                let threshold = curve.karma_for_level(level);
+                if threshold == u64::MAX {
+                    break;
+                }
+                // Наибольший уровень, порог которого не больше кармы
+                let expected = (0..=u8::MAX).rev().find(|l| curve.karma_for_level(*l) <= threshold).unwrap();
+                assert_eq!(curve.level_for_karma(threshold), expected, "{:?} at {}", curve, level);
+                if threshold > 0 {
+                    let below = (0..=u8::MAX).rev().find(|l| curve.karma_for_level(*l) < threshold).unwrap();
+                    assert_eq!(curve.level_for_karma(threshold - 1), below, "{:?} below {}", curve, level);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn curve_validity() {
+        for curve in CURVES {
+            assert!(curve.is_valid(), "{:?}", curve);
+        }
+        let invalid = [
+            LevelCurve::Linear { karma_per_level: 0 },
+            LevelCurve::Geometric { base_karma: 0, growth: 2 * ONE },
+            LevelCurve::Geometric { base_karma: 100, growth: ONE },
+            LevelCurve::Geometric { base_karma: 100, growth: 0 },
+        ];
+        for curve in invalid {
+            assert!(!curve.is_valid(), "{:?}", curve);
+        }
+    }
+
+    #[test]
+    fn boundary_karma() {
+        for curve in CURVES {
+            assert_eq!(curve.level_for_karma(0), 0);
+            assert_eq!(curve.karma_for_level(0), 0);
+        }
+        // (кривая, карма, уровень)
+        let cases = [
+            (LevelCurve::default(), u64::MAX, u8::MAX),
+            (LevelCurve::default(), KARMA_PER_LEVEL - 1, 0),
+            (LevelCurve::Linear { karma_per_level: 0 }, 0, u8::MAX),
+            (LevelCurve::Linear { karma_per_level: u64::MAX }, u64::MAX, 1),
+            (LevelCurve::Geometric { base_karma: 100, growth: 2 * ONE }, u64::MAX, 57),
+            (LevelCurve::Geometric { base_karma: 1_000, growth: ONE + 1 }, u64::MAX, u8::MAX),
+        ];
+        for (curve, karma, level) in cases {
+            assert_eq!(curve.level_for_karma(karma), level, "{:?} {}", curve, karma);
+        }
+        assert_eq!(LevelCurve::Linear { karma_per_level: u64::MAX }.karma_for_level(2), u64::MAX);
+        assert_eq!(LevelCurve::Geometric { base_karma: 100, growth: 2 * ONE }.karma_for_level(u8::MAX), u64::MAX);
+    }
+}
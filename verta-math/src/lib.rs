@@ -0,0 +1,9 @@
+// Общая математика Verta: используется on-chain программой, SDK и симулятором,
+// чтобы клиентские расчёты не расходились с поведением программы.
+// Крейт не зависит от std и собирается под BPF.
+#![no_std]
+
+pub mod decay;
+pub mod fixed;
+pub mod level;
+pub mod velocity;
@@ -0,0 +1,34 @@
+// Скользящее окно начисленной кармы по дням (ограничение скорости роста кармы)
+
+/// Количество дневных корзин в окне.
+pub const WINDOW_DAYS: usize = 7;
+
+/// Лимит кармы за окно по умолчанию.
+pub const DEFAULT_VELOCITY_LIMIT: u64 = 10_000;
+
+/// Длительность одной корзины в секундах.
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Номер дня для unix timestamp.
+pub fn day_index(unix_timestamp: i64) -> i64 {
+    unix_timestamp.div_euclid(SECONDS_PER_DAY)
+}
+
+/// Сдвигает окно так, чтобы `buckets[0]` соответствовала дню `today`.
+/// `window_day` — день, которому соответствует `buckets[0]` до сдвига.
+/// Корзины старше окна обнуляются.
+pub fn advance_window(buckets: &mut [u64; WINDOW_DAYS], window_day: i64, today: i64) {
+    if today <= window_day {
+        return;
+    }
+    let shift = (today - window_day).min(WINDOW_DAYS as i64) as usize;
+    buckets.rotate_right(shift);
+    for bucket in buckets.iter_mut().take(shift) {
+        *bucket = 0;
+    }
+}
+
+/// Сумма кармы во всех корзинах окна.
+pub fn window_total(buckets: &[u64; WINDOW_DAYS]) -> u64 {
+    buckets.iter().fold(0, |total, bucket| total.saturating_add(*bucket))
+}
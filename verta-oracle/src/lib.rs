@@ -0,0 +1,155 @@
+// Чтение репутации Verta из других on-chain программ без CPI.
+// Программа-потребитель получает PDA пользователя Verta среди аккаунтов своей инструкции
+// и читает его через load_reputation: проверяются владелец, дискриминатор, длина и адрес PDA.
+// Смещения полей повторяют раскладку UserAccount программы Verta (схема версии 14)
+// и должны меняться вместе с ней (тесты сверяют их с сериализацией UserAccount).
+// Аккаунты старых схем не читаются: их нужно сначала обновить инструкцией MigrateAccount.
+
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+/// Дискриминатор аккаунта пользователя Verta
+pub const USER_DISCRIMINATOR: [u8; 8] = *b"vrt:user";
+/// Сид PDA аккаунта пользователя
+pub const USER_SEED: &[u8] = b"user";
+/// Число категорий кармы
+pub const KARMA_CATEGORIES: usize = 4;
+/// Поддерживаемая версия схемы аккаунта пользователя
+pub const USER_ACCOUNT_SCHEMA_VERSION: u8 = 14;
+
+// Смещения полей UserAccount в данных аккаунта
+const VERSION_OFFSET: usize = 8;
+const KARMA_OFFSET: usize = 9;
+const LEVEL_OFFSET: usize = 17;
+const OVERFLOW_KARMA_OFFSET: usize = 122;
+const OWNER_OFFSET: usize = 130;
+const BUMP_OFFSET: usize = 162;
+const REGISTERED_AT_OFFSET: usize = 163;
+const LAST_UPDATED_OFFSET: usize = 171;
+const CATEGORY_KARMA_OFFSET: usize = 179;
+const FROZEN_OFFSET: usize = 219;
+/// Минимальная длина данных аккаунта пользователя
+pub const USER_ACCOUNT_LEN: usize = 322;
+
+/// Репутация пользователя Verta, прочитанная из его PDA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reputation {
+    pub wallet: Pubkey,                          // Кошелёк пользователя
+    pub karma: u64,                              // Текущая карма
+    pub level: u8,                               // Уровень
+    pub overflow_karma: u64,                     // Карма сверх максимального уровня
+    pub category_karma: [u64; KARMA_CATEGORIES], // Заработанная карма по категориям
+    pub registered_at: i64,                      // Время регистрации (unix timestamp)
+    pub last_updated: i64,                       // Время последнего изменения (unix timestamp)
+    pub frozen: bool,                            // Аккаунт заморожен модератором Verta
+}
+
+/// Читает репутацию из PDA пользователя, принадлежащего программе Verta `program_id`.
+pub fn load_reputation(account_info: &AccountInfo, program_id: &Pubkey) -> Result<Reputation, ProgramError> {
+    if account_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = account_info.try_borrow_data()?;
+    let reputation = parse_reputation(&data)?;
+
+    let bump = data[BUMP_OFFSET];
+    let expected_pda = Pubkey::create_program_address(&[USER_SEED, reputation.wallet.as_ref(), &[bump]], program_id)
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+    if expected_pda != *account_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(reputation)
+}
+
+/// Разбирает данные аккаунта пользователя без проверки владельца и адреса.
+pub fn parse_reputation(data: &[u8]) -> Result<Reputation, ProgramError> {
+    if data.len() < USER_ACCOUNT_LEN {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    if data[..8] != USER_DISCRIMINATOR || data[VERSION_OFFSET] != USER_ACCOUNT_SCHEMA_VERSION {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut category_karma = [0u64; KARMA_CATEGORIES];
+    for (i, karma) in category_karma.iter_mut().enumerate() {
+        *karma = read_u64(data, CATEGORY_KARMA_OFFSET + 8 * i);
+    }
+    Ok(Reputation {
+        wallet: Pubkey::new_from_array(data[OWNER_OFFSET..OWNER_OFFSET + 32].try_into().unwrap()),
+        karma: read_u64(data, KARMA_OFFSET),
+        level: data[LEVEL_OFFSET],
+        overflow_karma: read_u64(data, OVERFLOW_KARMA_OFFSET),
+        category_karma,
+        registered_at: read_u64(data, REGISTERED_AT_OFFSET) as i64,
+        last_updated: read_u64(data, LAST_UPDATED_OFFSET) as i64,
+        frozen: data[FROZEN_OFFSET] != 0,
+    })
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use verta_project_v2::{UserAccount, VertaAccount};
+
+    fn serialized(account: &UserAccount) -> Vec<u8> {
+        let mut data = vec![0; UserAccount::LEN];
+        account.write_account_data(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn layout_matches_user_account() {
+        assert_eq!(USER_ACCOUNT_SCHEMA_VERSION, verta_project_v2::USER_ACCOUNT_SCHEMA_VERSION);
+        assert_eq!(USER_ACCOUNT_LEN, UserAccount::LEN);
+        assert_eq!(USER_DISCRIMINATOR, UserAccount::DISCRIMINATOR);
+        assert_eq!(KARMA_CATEGORIES, verta_project_v2::KARMA_CATEGORIES);
+        assert_eq!(VERSION_OFFSET, UserAccount::VERSION_OFFSET);
+        assert_eq!(KARMA_OFFSET, UserAccount::KARMA_OFFSET);
+        assert_eq!(LEVEL_OFFSET, UserAccount::LEVEL_OFFSET);
+        assert_eq!(OWNER_OFFSET, UserAccount::OWNER_OFFSET);
+    }
+
+    #[test]
+    fn parses_serialized_user_account() {
+        // Все поля различны: перепутанное смещение даст другое значение
+        let account = UserAccount::builder()
+            .karma(1_234)
+            .level(7)
+            .last_level_up(11)
+            .identity_hash([0xAA; 32])
+            .overflow_karma(5_678)
+            .owner(Pubkey::new_unique())
+            .bump(253)
+            .registered_at(1_700_000_001)
+            .last_updated(1_700_000_002)
+            .category_karma([10, 20, 30, 40])
+            .grant_nonce(99)
+            .frozen(true)
+            .last_decay_at(1_700_000_003)
+            .rent_payer(Pubkey::new_unique())
+            .build();
+        let data = serialized(&account);
+        assert_eq!(data[BUMP_OFFSET], account.bump);
+        assert_eq!(
+            parse_reputation(&data),
+            Ok(Reputation {
+                wallet: account.owner,
+                karma: account.karma,
+                level: account.level,
+                overflow_karma: account.overflow_karma,
+                category_karma: account.category_karma,
+                registered_at: account.registered_at,
+                last_updated: account.last_updated,
+                frozen: account.frozen,
+            })
+        );
+
+        // Аккаунт старой схемы не читается
+        let old = serialized(&UserAccount { version: USER_ACCOUNT_SCHEMA_VERSION - 1, ..account });
+        assert_eq!(parse_reputation(&old), Err(ProgramError::InvalidAccountData));
+        assert_eq!(parse_reputation(&data[..USER_ACCOUNT_LEN - 1]), Err(ProgramError::AccountDataTooSmall));
+    }
+}
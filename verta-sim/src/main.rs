@@ -0,0 +1,53 @@
+// verta-sim: прогон сценариев экономики кармы до изменения параметров on-chain.
+// Использование: verta-sim <scenario.toml>
+// Статистика выводится в stdout в формате CSV, гистограмма уровней — в конце.
+
+mod scenario;
+mod sim;
+
+use scenario::Scenario;
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: verta-sim <scenario.toml>");
+        std::process::exit(2);
+    };
+    let scenario: Scenario = match std::fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read {}: {}", path, err))
+        .and_then(|text| toml::from_str(&text).map_err(|err| format!("invalid scenario: {}", err)))
+    {
+        Ok(scenario) => scenario,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    if let Err(err) = scenario.validate() {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    }
+
+    let report = sim::run(&scenario);
+    println!("day,mean_karma,p50_karma,p90_karma,p99_karma,max_karma,mean_level,max_level_share,granted,rejected_grants");
+    for stats in &report.stats {
+        println!(
+            "{},{},{},{},{},{},{:.2},{:.4},{},{}",
+            stats.day,
+            stats.mean_karma,
+            stats.p50_karma,
+            stats.p90_karma,
+            stats.p99_karma,
+            stats.max_karma,
+            stats.mean_level,
+            stats.max_level_share,
+            stats.granted,
+            stats.rejected_grants
+        );
+    }
+
+    println!();
+    println!("level,users");
+    for (level, users) in report.level_histogram.iter().enumerate().filter(|(_, users)| **users > 0) {
+        println!("{},{}", level, users);
+    }
+}
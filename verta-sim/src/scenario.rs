@@ -0,0 +1,135 @@
+// Описание сценария симуляции (читается из TOML)
+
+use serde::Deserialize;
+use verta_math::{
+    fixed,
+    level::{LevelCurve, DEFAULT_MAX_LEVEL},
+    velocity::DEFAULT_VELOCITY_LIMIT,
+};
+
+
+/// Сценарий: параметры программы, популяция и события по дням.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    pub days: u32, // Длительность симуляции в днях
+    #[serde(default = "default_seed")]
+    pub seed: u64, // Сид генератора: одинаковый сценарий даёт одинаковый результат
+    #[serde(default = "default_report_every")]
+    pub report_every: u32, // Период вывода статистики в днях
+    #[serde(default)]
+    pub params: Params,
+    pub cohorts: Vec<Cohort>,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+}
+
+fn default_seed() -> u64 {
+    1
+}
+
+fn default_report_every() -> u32 {
+    7
+}
+
+/// Настраиваемые параметры программы.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Params {
+    pub curve: Curve,
+    pub max_karma_per_grant: u64,
+    pub velocity_limit: u64,  // Лимит кармы за окно скорости; 0 — без лимита
+    pub karma_half_life: u64, // Период полураспада в секундах; 0 — без затухания
+    pub max_level: u8,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            curve: Curve::default(),
+            max_karma_per_grant: 1_000,
+            velocity_limit: DEFAULT_VELOCITY_LIMIT,
+            karma_half_life: 0,
+            max_level: DEFAULT_MAX_LEVEL,
+        }
+    }
+}
+
+/// Кривая уровней в виде, удобном для конфигурации.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Curve {
+    Linear { karma_per_level: u64 },
+    /// `growth` — множитель стоимости уровня, например 1.15
+    Geometric { base_karma: u64, growth: f64 },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Linear {
+            karma_per_level: verta_math::level::KARMA_PER_LEVEL,
+        }
+    }
+}
+
+impl Curve {
+    pub fn level_curve(&self) -> LevelCurve {
+        match *self {
+            Curve::Linear { karma_per_level } => LevelCurve::Linear { karma_per_level },
+            Curve::Geometric { base_karma, growth } => LevelCurve::Geometric {
+                base_karma,
+                growth: (growth * fixed::SCALE as f64).round() as u64,
+            },
+        }
+    }
+}
+
+/// Группа пользователей с одинаковым поведением.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Cohort {
+    pub name: String,
+    pub size: u32,
+    pub grants_per_day: f64, // Среднее число начислений в день (дробная часть — вероятность ещё одного)
+    pub grant_min: u64,
+    pub grant_max: u64,
+    #[serde(default)]
+    pub join_day: u32, // День, с которого группа начинает получать карму
+}
+
+/// Сезон: с дня `start_day` меняется множитель начислений, при `reset_karma` карма обнуляется.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Season {
+    pub start_day: u32,
+    #[serde(default = "default_multiplier")]
+    pub grant_multiplier: f64,
+    #[serde(default)]
+    pub reset_karma: bool,
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+impl Scenario {
+    /// Проверяет согласованность сценария.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.report_every == 0 {
+            return Err("report_every must be positive".to_string());
+        }
+        if self.params.max_karma_per_grant == 0 {
+            return Err("max_karma_per_grant must be positive".to_string());
+        }
+        if self.params.max_level == 0 {
+            return Err("max_level must be positive".to_string());
+        }
+        // Та же проверка, что и у кривой из конфигурации программы
+        if !self.params.curve.level_curve().is_valid() {
+            return Err("level curve needs a positive level cost and geometric growth greater than 1".to_string());
+        }
+        for cohort in &self.cohorts {
+            if cohort.grant_min > cohort.grant_max || cohort.grants_per_day < 0.0 {
+                return Err(format!("cohort {} has invalid grant settings", cohort.name));
+            }
+        }
+        Ok(())
+    }
+}
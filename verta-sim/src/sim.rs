@@ -0,0 +1,164 @@
+// Симуляция популяции пользователей на общей математике verta-math.
+// Каждый день: затухание (как при ежедневном вызове DecayKarma), сезонные события
+// и начисления с теми же лимитами, что и в программе (лимит начисления, окно скорости,
+// кап кармы на максимальном уровне с переносом остатка в overflow).
+
+use verta_math::{
+    decay::decay,
+    level::LevelCurve,
+    velocity::{advance_window, window_total, SECONDS_PER_DAY, WINDOW_DAYS},
+};
+
+use crate::scenario::Scenario;
+
+// Состояние одного пользователя
+#[derive(Default)]
+struct User {
+    cohort: usize,
+    karma: u64,
+    overflow_karma: u64,
+    karma_window: [u64; WINDOW_DAYS],
+    window_day: i64,
+}
+
+/// Статистика популяции на конец дня.
+#[derive(Debug, Clone)]
+pub struct DayStats {
+    pub day: u32,
+    pub mean_karma: u64,
+    pub p50_karma: u64,
+    pub p90_karma: u64,
+    pub p99_karma: u64,
+    pub max_karma: u64,
+    pub mean_level: f64,
+    pub max_level_share: f64, // Доля пользователей на максимальном уровне
+    pub granted: u64,         // Карма, начисленная за день
+    pub rejected_grants: u64, // Начисления, отклонённые лимитом скорости
+}
+
+/// Результат симуляции.
+pub struct Report {
+    pub stats: Vec<DayStats>,
+    pub level_histogram: Vec<u32>, // Число пользователей на каждом уровне в конце симуляции
+}
+
+// Детерминированный генератор xorshift64*
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // Равномерно в [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Равномерно в [min, max]
+    fn range(&mut self, min: u64, max: u64) -> u64 {
+        min + self.next_u64() % (max - min + 1)
+    }
+}
+
+/// Прогоняет сценарий и собирает статистику каждые `report_every` дней.
+pub fn run(scenario: &Scenario) -> Report {
+    let curve = scenario.params.curve.level_curve();
+    let max_level = scenario.params.max_level;
+    let karma_cap = curve.karma_for_level(max_level);
+    let mut rng = Rng::new(scenario.seed);
+    let mut users: Vec<User> = scenario
+        .cohorts
+        .iter()
+        .enumerate()
+        .flat_map(|(cohort, group)| (0..group.size).map(move |_| User { cohort, ..User::default() }))
+        .collect();
+
+    let mut multiplier = 1.0;
+    let mut stats = Vec::new();
+    for day in 1..=scenario.days {
+        for season in scenario.seasons.iter().filter(|season| season.start_day == day) {
+            multiplier = season.grant_multiplier;
+            if season.reset_karma {
+                for user in users.iter_mut() {
+                    user.karma = 0;
+                    user.overflow_karma = 0;
+                }
+            }
+        }
+
+        let mut granted = 0u64;
+        let mut rejected_grants = 0u64;
+        for user in users.iter_mut() {
+            user.karma = decay(user.karma, SECONDS_PER_DAY as u64, scenario.params.karma_half_life);
+
+            let cohort = &scenario.cohorts[user.cohort];
+            if day < cohort.join_day {
+                continue;
+            }
+            advance_window(&mut user.karma_window, user.window_day, day as i64);
+            user.window_day = day as i64;
+
+            let mut grants = cohort.grants_per_day.floor() as u64;
+            if rng.next_f64() < cohort.grants_per_day.fract() {
+                grants += 1;
+            }
+            for _ in 0..grants {
+                let amount = (rng.range(cohort.grant_min, cohort.grant_max) as f64 * multiplier) as u64;
+                let amount = amount.min(scenario.params.max_karma_per_grant);
+                let velocity_limit = scenario.params.velocity_limit;
+                if velocity_limit > 0 && window_total(&user.karma_window).saturating_add(amount) > velocity_limit {
+                    rejected_grants += 1;
+                    continue;
+                }
+                user.karma_window[0] += amount;
+                let to_karma = amount.min(karma_cap.saturating_sub(user.karma));
+                user.karma += to_karma;
+                user.overflow_karma += amount - to_karma;
+                granted += amount;
+            }
+        }
+
+        if day % scenario.report_every == 0 || day == scenario.days {
+            stats.push(day_stats(day, &users, &curve, max_level, granted, rejected_grants));
+        }
+    }
+
+    let mut level_histogram = vec![0u32; max_level as usize + 1];
+    for user in &users {
+        level_histogram[level(&curve, max_level, user.karma) as usize] += 1;
+    }
+    Report { stats, level_histogram }
+}
+
+fn level(curve: &LevelCurve, max_level: u8, karma: u64) -> u8 {
+    curve.level_for_karma(karma).min(max_level)
+}
+
+fn day_stats(day: u32, users: &[User], curve: &LevelCurve, max_level: u8, granted: u64, rejected_grants: u64) -> DayStats {
+    let mut karma: Vec<u64> = users.iter().map(|user| user.karma).collect();
+    karma.sort_unstable();
+    let percentile = |p: usize| karma.get((karma.len().saturating_sub(1)) * p / 100).copied().unwrap_or(0);
+    let count = users.len().max(1);
+    let levels: Vec<u8> = karma.iter().map(|karma| level(curve, max_level, *karma)).collect();
+
+    DayStats {
+        day,
+        mean_karma: karma.iter().sum::<u64>() / count as u64,
+        p50_karma: percentile(50),
+        p90_karma: percentile(90),
+        p99_karma: percentile(99),
+        max_karma: karma.last().copied().unwrap_or(0),
+        mean_level: levels.iter().map(|level| *level as f64).sum::<f64>() / count as f64,
+        max_level_share: levels.iter().filter(|level| **level == max_level).count() as f64 / count as f64,
+        granted,
+        rejected_grants,
+    }
+}